@@ -213,6 +213,74 @@ int raptorq_decoder_get_data(
     size_t* out_len
 );
 
+/* ============================================================================
+ * Integrity API
+ *
+ * Appends a checksum to each serialized packet, so that corrupted packets are
+ * dropped instead of corrupting the decoded data. The wire format is the
+ * packet followed by its checksum in big-endian byte order, as produced by
+ * the Rust IntegrityCodec::serialize() and the WASM seal_packet().
+ * ============================================================================ */
+
+/** CRC-32C checksum, 4 bytes per packet */
+#define RAPTORQ_INTEGRITY_CRC32C 1
+/** xxHash64 checksum with a seed of 0, 8 bytes per packet */
+#define RAPTORQ_INTEGRITY_XXHASH64 2
+
+/**
+ * Get the number of bytes a codec appends to each packet
+ *
+ * @return Checksum size, or 0 if the codec is unknown
+ */
+size_t raptorq_integrity_checksum_size(uint32_t codec);
+
+/**
+ * Append a checksum to a serialized packet
+ *
+ * @param codec      RAPTORQ_INTEGRITY_CRC32C or RAPTORQ_INTEGRITY_XXHASH64
+ * @param packet     Serialized packet (4-byte PayloadId + symbol)
+ * @param packet_len Packet length
+ * @param out_data   Output buffer (packet_len + checksum size bytes)
+ * @param max_len    Output buffer size
+ * @param out_len    On output: bytes written
+ *
+ * @return 0 on success, -1 on error
+ */
+int raptorq_integrity_seal(
+    uint32_t codec,
+    const uint8_t* packet,
+    size_t packet_len,
+    uint8_t* out_data,
+    size_t max_len,
+    size_t* out_len
+);
+
+/**
+ * Verify the checksum of a packet, and add it to the decoder
+ *
+ * Packets which have the wrong length or fail verification are dropped, and
+ * counted by raptorq_decoder_rejected_packets().
+ *
+ * @param decoder    Decoder handle
+ * @param codec      RAPTORQ_INTEGRITY_CRC32C or RAPTORQ_INTEGRITY_XXHASH64
+ * @param packet     Packet created by raptorq_integrity_seal()
+ * @param packet_len Packet length
+ *
+ * @return 1 if decoding complete, 0 if more packets needed, -1 on invalid
+ *         arguments, -2 if the packet was dropped
+ */
+int raptorq_decoder_add_verified_packet(
+    RaptorQDecoder* decoder,
+    uint32_t codec,
+    const uint8_t* packet,
+    size_t packet_len
+);
+
+/**
+ * Get the number of packets dropped by raptorq_decoder_add_verified_packet()
+ */
+uint64_t raptorq_decoder_rejected_packets(const RaptorQDecoder* decoder);
+
 /* ============================================================================
  * Utility Functions
  * ============================================================================ */
@@ -4,7 +4,10 @@
 //!
 //! Includes both standard RaptorQ API and interleaved FEC API for reduced latency.
 
-use raptorq::{Decoder, Encoder, EncodingPacket, ObjectTransmissionInformation};
+use raptorq::{
+    Crc32c, Decoder, Encoder, EncodingPacket, IntegrityCodec, ObjectTransmissionInformation,
+    XxHash64,
+};
 use std::ptr;
 use std::slice;
 
@@ -34,6 +37,7 @@ pub struct RaptorQDecoderC {
     config: ObjectTransmissionInformation,
     is_complete: bool,
     decoded_data: Option<Vec<u8>>,
+    rejected_packets: u64,
 }
 
 /// Result structure for encoding operations
@@ -291,6 +295,7 @@ pub extern "C" fn raptorq_decoder_new(oti: *const RaptorQOTI) -> *mut RaptorQDec
         config,
         is_complete: false,
         decoded_data: None,
+        rejected_packets: 0,
     });
 
     Box::into_raw(dec)
@@ -319,6 +324,7 @@ pub extern "C" fn raptorq_decoder_new_with_params(
         config,
         is_complete: false,
         decoded_data: None,
+        rejected_packets: 0,
     });
 
     Box::into_raw(dec)
@@ -461,6 +467,147 @@ pub extern "C" fn raptorq_decoder_get_data(
     }
 }
 
+// ============================================================================
+// Integrity API
+// ============================================================================
+
+/// CRC-32C checksum, 4 bytes per packet
+pub const RAPTORQ_INTEGRITY_CRC32C: u32 = 1;
+/// xxHash64 checksum with a seed of 0, 8 bytes per packet
+pub const RAPTORQ_INTEGRITY_XXHASH64: u32 = 2;
+
+fn integrity_codec(codec: u32) -> Option<Box<dyn IntegrityCodec>> {
+    match codec {
+        RAPTORQ_INTEGRITY_CRC32C => Some(Box::new(Crc32c)),
+        RAPTORQ_INTEGRITY_XXHASH64 => Some(Box::new(XxHash64::default())),
+        _ => None,
+    }
+}
+
+/// Get the number of bytes a codec appends to each packet
+///
+/// # Returns
+/// Checksum size, or 0 if the codec is unknown
+#[no_mangle]
+pub extern "C" fn raptorq_integrity_checksum_size(codec: u32) -> usize {
+    integrity_codec(codec).map_or(0, |codec| codec.checksum_size())
+}
+
+/// Append a checksum to a serialized packet
+///
+/// # Arguments
+/// * `codec` - RAPTORQ_INTEGRITY_CRC32C or RAPTORQ_INTEGRITY_XXHASH64
+/// * `packet` - Serialized packet (4-byte PayloadId + symbol)
+/// * `packet_len` - Packet length
+/// * `out_data` - Output buffer (packet_len + checksum size bytes)
+/// * `max_len` - Output buffer size
+/// * `out_len` - On output: bytes written
+///
+/// # Returns
+/// 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn raptorq_integrity_seal(
+    codec: u32,
+    packet: *const u8,
+    packet_len: usize,
+    out_data: *mut u8,
+    max_len: usize,
+    out_len: *mut usize,
+) -> i32 {
+    if packet.is_null() || out_data.is_null() || out_len.is_null() || packet_len < 4 {
+        return -1;
+    }
+    let codec = match integrity_codec(codec) {
+        Some(codec) => codec,
+        None => return -1,
+    };
+
+    let packet_slice = unsafe { slice::from_raw_parts(packet, packet_len) };
+    let sealed = codec.serialize(&EncodingPacket::deserialize(packet_slice));
+    if sealed.len() > max_len {
+        return -1; // Buffer too small
+    }
+
+    unsafe {
+        ptr::copy_nonoverlapping(sealed.as_ptr(), out_data, sealed.len());
+        *out_len = sealed.len();
+    }
+
+    0
+}
+
+/// Verify the checksum of a packet, and add it to the decoder
+///
+/// Packets which have the wrong length or fail verification are dropped, and counted by
+/// raptorq_decoder_rejected_packets().
+///
+/// # Arguments
+/// * `decoder` - Decoder handle
+/// * `codec` - RAPTORQ_INTEGRITY_CRC32C or RAPTORQ_INTEGRITY_XXHASH64
+/// * `packet` - Packet created by raptorq_integrity_seal()
+/// * `packet_len` - Packet length
+///
+/// # Returns
+/// 1 if decoding is now complete, 0 if more packets needed, -1 on invalid arguments,
+/// -2 if the packet was dropped
+#[no_mangle]
+pub extern "C" fn raptorq_decoder_add_verified_packet(
+    decoder: *mut RaptorQDecoderC,
+    codec: u32,
+    packet: *const u8,
+    packet_len: usize,
+) -> i32 {
+    if decoder.is_null() || packet.is_null() {
+        return -1;
+    }
+    let codec = match integrity_codec(codec) {
+        Some(codec) => codec,
+        None => return -1,
+    };
+
+    let dec = unsafe { &mut *decoder };
+
+    if dec.is_complete {
+        return 1;
+    }
+
+    let expected_size = 4 + dec.config.symbol_size() as usize + codec.checksum_size();
+    if packet_len != expected_size {
+        dec.rejected_packets += 1;
+        return -2;
+    }
+
+    let packet_slice = unsafe { slice::from_raw_parts(packet, packet_len) };
+    let result = match codec
+        .deserialize(packet_slice)
+        .and_then(|packet| dec.decoder.try_decode(packet))
+    {
+        Ok(result) => result,
+        Err(_) => {
+            dec.rejected_packets += 1;
+            return -2;
+        }
+    };
+
+    if let Some(data) = result {
+        dec.is_complete = true;
+        dec.decoded_data = Some(data);
+        return 1;
+    }
+
+    0
+}
+
+/// Get the number of packets dropped by raptorq_decoder_add_verified_packet()
+#[no_mangle]
+pub extern "C" fn raptorq_decoder_rejected_packets(decoder: *const RaptorQDecoderC) -> u64 {
+    if decoder.is_null() {
+        return 0;
+    }
+    let dec = unsafe { &*decoder };
+    dec.rejected_packets
+}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
@@ -546,4 +693,79 @@ mod tests {
         raptorq_encoder_free(encoder);
         raptorq_decoder_free(decoder);
     }
+
+    #[test]
+    fn test_verified_packets() {
+        let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let encoder = raptorq_encoder_new(data.as_ptr(), data.len(), 128, 5);
+        let mut oti = RaptorQOTI { bytes: [0; 12] };
+        assert_eq!(raptorq_encoder_get_oti(encoder, &mut oti), 0);
+        let packets = unsafe { &(*encoder).encoder }.get_encoded_packets(5);
+        raptorq_encoder_free(encoder);
+
+        let checksum_size = raptorq_integrity_checksum_size(RAPTORQ_INTEGRITY_XXHASH64);
+        assert_eq!(checksum_size, 8);
+        let decoder = raptorq_decoder_new(&oti);
+        // Truncated packets are dropped too
+        let truncated = [0u8; 10];
+        assert_eq!(
+            raptorq_decoder_add_verified_packet(
+                decoder,
+                RAPTORQ_INTEGRITY_XXHASH64,
+                truncated.as_ptr(),
+                truncated.len(),
+            ),
+            -2
+        );
+        let mut result = 0;
+        for (i, packet) in packets.iter().enumerate() {
+            let serialized = packet.serialize();
+            let mut sealed = vec![0; serialized.len() + checksum_size];
+            let mut sealed_len = 0;
+            assert_eq!(
+                raptorq_integrity_seal(
+                    RAPTORQ_INTEGRITY_XXHASH64,
+                    serialized.as_ptr(),
+                    serialized.len(),
+                    sealed.as_mut_ptr(),
+                    sealed.len(),
+                    &mut sealed_len,
+                ),
+                0
+            );
+            assert_eq!(sealed_len, sealed.len());
+            // Corrupt the first packet, which a repair packet makes up for
+            if i == 0 {
+                sealed[4] ^= 1;
+            }
+            result = raptorq_decoder_add_verified_packet(
+                decoder,
+                RAPTORQ_INTEGRITY_XXHASH64,
+                sealed.as_ptr(),
+                sealed.len(),
+            );
+            if i == 0 {
+                assert_eq!(result, -2);
+            }
+            if result == 1 {
+                break;
+            }
+        }
+        assert_eq!(result, 1);
+        assert_eq!(raptorq_decoder_rejected_packets(decoder), 2);
+
+        let mut decoded = vec![0; data.len()];
+        let mut decoded_len = 0;
+        assert_eq!(
+            raptorq_decoder_get_data(
+                decoder,
+                decoded.as_mut_ptr(),
+                decoded.len(),
+                &mut decoded_len
+            ),
+            0
+        );
+        assert_eq!(decoded, data);
+        raptorq_decoder_free(decoder);
+    }
 }
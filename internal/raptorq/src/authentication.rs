@@ -0,0 +1,532 @@
+#[cfg(feature = "std")]
+use std::{fmt, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::base::{EncodingPacket, ObjectTransmissionInformation, PAYLOAD_ID_SIZE};
+use crate::decoder::Decoder;
+use crate::error::RaptorQError;
+
+/// A message authentication code, used by [`PacketAuthenticator`] to reject forged packets.
+pub trait PacketMac {
+    /// Number of bytes the tag occupies on the wire
+    fn tag_size(&self) -> usize;
+
+    /// Computes the tag of `message` with `key`. Must return [`PacketMac::tag_size`] bytes
+    fn tag(&self, key: &[u8], message: &[u8]) -> Vec<u8>;
+}
+
+/// HMAC-SHA256, as specified in RFC 2104 and FIPS 180-4
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HmacSha256;
+
+const SHA256_BLOCK_SIZE: usize = 64;
+const SHA256_DIGEST_SIZE: usize = 32;
+
+// See section 4.2.2 of FIPS 180-4
+#[rustfmt::skip]
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428A_2F98, 0x7137_4491, 0xB5C0_FBCF, 0xE9B5_DBA5, 0x3956_C25B, 0x59F1_11F1,
+    0x923F_82A4, 0xAB1C_5ED5, 0xD807_AA98, 0x1283_5B01, 0x2431_85BE, 0x550C_7DC3,
+    0x72BE_5D74, 0x80DE_B1FE, 0x9BDC_06A7, 0xC19B_F174, 0xE49B_69C1, 0xEFBE_4786,
+    0x0FC1_9DC6, 0x240C_A1CC, 0x2DE9_2C6F, 0x4A74_84AA, 0x5CB0_A9DC, 0x76F9_88DA,
+    0x983E_5152, 0xA831_C66D, 0xB003_27C8, 0xBF59_7FC7, 0xC6E0_0BF3, 0xD5A7_9147,
+    0x06CA_6351, 0x1429_2967, 0x27B7_0A85, 0x2E1B_2138, 0x4D2C_6DFC, 0x5338_0D13,
+    0x650A_7354, 0x766A_0ABB, 0x81C2_C92E, 0x9272_2C85, 0xA2BF_E8A1, 0xA81A_664B,
+    0xC24B_8B70, 0xC76C_51A3, 0xD192_E819, 0xD699_0624, 0xF40E_3585, 0x106A_A070,
+    0x19A4_C116, 0x1E37_6C08, 0x2748_774C, 0x34B0_BCB5, 0x391C_0CB3, 0x4ED8_AA4A,
+    0x5B9C_CA4F, 0x682E_6FF3, 0x748F_82EE, 0x78A5_636F, 0x84C8_7814, 0x8CC7_0208,
+    0x90BE_FFFA, 0xA450_6CEB, 0xBEF9_A3F7, 0xC671_78F2,
+];
+
+// See section 5.3.3 of FIPS 180-4
+#[rustfmt::skip]
+const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6A09_E667, 0xBB67_AE85, 0x3C6E_F372, 0xA54F_F53A,
+    0x510E_527F, 0x9B05_688C, 0x1F83_D9AB, 0x5BE0_CD19,
+];
+
+struct Sha256 {
+    state: [u32; 8],
+    buffer: [u8; SHA256_BLOCK_SIZE],
+    buffered: usize,
+    length: u64,
+}
+
+impl Sha256 {
+    fn new() -> Sha256 {
+        Sha256 {
+            state: SHA256_INITIAL_STATE,
+            buffer: [0; SHA256_BLOCK_SIZE],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+        if self.buffered > 0 {
+            let copied = data.len().min(SHA256_BLOCK_SIZE - self.buffered);
+            self.buffer[self.buffered..self.buffered + copied].copy_from_slice(&data[..copied]);
+            self.buffered += copied;
+            data = &data[copied..];
+            if self.buffered < SHA256_BLOCK_SIZE {
+                return;
+            }
+            let block = self.buffer;
+            self.compress(&block);
+            self.buffered = 0;
+        }
+        let mut blocks = data.chunks_exact(SHA256_BLOCK_SIZE);
+        for block in blocks.by_ref() {
+            self.compress(block.try_into().unwrap());
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    fn finalize(mut self) -> [u8; SHA256_DIGEST_SIZE] {
+        let bit_length = self.length.wrapping_mul(8);
+        // Pad with a one bit, then zeros until 8 bytes remain for the length
+        let padding = [0x80];
+        self.update(&padding);
+        while self.buffered != SHA256_BLOCK_SIZE - 8 {
+            self.update(&[0]);
+        }
+        self.update(&bit_length.to_be_bytes());
+
+        let mut digest = [0; SHA256_DIGEST_SIZE];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8; SHA256_BLOCK_SIZE]) {
+        let mut schedule = [0u32; 64];
+        for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = schedule[i - 15].rotate_right(7)
+                ^ schedule[i - 15].rotate_right(18)
+                ^ (schedule[i - 15] >> 3);
+            let s1 = schedule[i - 2].rotate_right(17)
+                ^ schedule[i - 2].rotate_right(19)
+                ^ (schedule[i - 2] >> 10);
+            schedule[i] = schedule[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(schedule[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for (round_constant, word) in SHA256_ROUND_CONSTANTS.iter().zip(schedule) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(*round_constant)
+                .wrapping_add(word);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+impl PacketMac for HmacSha256 {
+    fn tag_size(&self) -> usize {
+        SHA256_DIGEST_SIZE
+    }
+
+    fn tag(&self, key: &[u8], message: &[u8]) -> Vec<u8> {
+        // Keys longer than a block are hashed first. See section 2 of RFC 2104
+        let mut block_key = [0; SHA256_BLOCK_SIZE];
+        if key.len() > SHA256_BLOCK_SIZE {
+            let mut hasher = Sha256::new();
+            hasher.update(key);
+            block_key[..SHA256_DIGEST_SIZE].copy_from_slice(&hasher.finalize());
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = Sha256::new();
+        inner.update(&block_key.map(|byte| byte ^ 0x36));
+        inner.update(message);
+        let inner_digest = inner.finalize();
+
+        let mut outer = Sha256::new();
+        outer.update(&block_key.map(|byte| byte ^ 0x5C));
+        outer.update(&inner_digest);
+        outer.finalize().to_vec()
+    }
+}
+
+// Compares in time which only depends on the length, so that tags can't be guessed byte by byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Authenticates serialized [`EncodingPacket`]s with a keyed [`PacketMac`], so that packets
+/// injected by anyone without the key can be dropped before they reach the decoder.
+///
+/// The wire format is the ID of the key, followed by the serialized packet, followed by the tag.
+/// The tag covers the key ID and the serialized packet, including its
+/// [`PayloadId`](crate::PayloadId), and is also computed over the serialized
+/// [`ObjectTransmissionInformation`] of the object, so that packets cannot be replayed into the
+/// decoder of a different object.
+///
+/// Multiple keys can be known at once, to allow them to be rotated: packets are always sealed
+/// with the signing key, but can be opened with any known key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PacketAuthenticator<M: PacketMac> {
+    mac: M,
+    config: ObjectTransmissionInformation,
+    keys: Vec<(u8, Vec<u8>)>,
+    signing_key_id: Option<u8>,
+}
+
+impl<M: PacketMac> PacketAuthenticator<M> {
+    pub fn new(mac: M, config: ObjectTransmissionInformation) -> PacketAuthenticator<M> {
+        PacketAuthenticator {
+            mac,
+            config,
+            keys: vec![],
+            signing_key_id: None,
+        }
+    }
+
+    /// Adds a key which packets can be opened with, replacing any existing key with the same ID.
+    /// The first key added becomes the signing key.
+    pub fn add_key(&mut self, key_id: u8, key: &[u8]) {
+        self.keys.retain(|(id, _)| *id != key_id);
+        self.keys.push((key_id, key.to_vec()));
+        if self.signing_key_id.is_none() {
+            self.signing_key_id = Some(key_id);
+        }
+    }
+
+    /// Removes a key, so that packets authenticated with it are rejected. Returns whether the key
+    /// was known. If it was the signing key, no packets can be sealed until another is set.
+    pub fn remove_key(&mut self, key_id: u8) -> bool {
+        let known = self.keys.len();
+        self.keys.retain(|(id, _)| *id != key_id);
+        if self.signing_key_id == Some(key_id) {
+            self.signing_key_id = None;
+        }
+        self.keys.len() != known
+    }
+
+    /// Sets the key which packets are sealed with
+    pub fn set_signing_key(&mut self, key_id: u8) -> Result<(), RaptorQError> {
+        if self.key(key_id).is_none() {
+            return Err(RaptorQError::UnknownKeyId(key_id));
+        }
+        self.signing_key_id = Some(key_id);
+        Ok(())
+    }
+
+    pub fn signing_key_id(&self) -> Option<u8> {
+        self.signing_key_id
+    }
+
+    pub fn config(&self) -> &ObjectTransmissionInformation {
+        &self.config
+    }
+
+    /// Serializes and authenticates `packet`
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no signing key. Use [`PacketAuthenticator::try_seal`] to get an error
+    /// instead.
+    pub fn seal(&self, packet: &EncodingPacket) -> Vec<u8> {
+        self.try_seal(packet).unwrap()
+    }
+
+    /// Like [`PacketAuthenticator::seal`], but returns an error if there is no signing key
+    pub fn try_seal(&self, packet: &EncodingPacket) -> Result<Vec<u8>, RaptorQError> {
+        let key_id = self.signing_key_id.ok_or(RaptorQError::NoSigningKey)?;
+        let mut sealed = Vec::with_capacity(1 + PAYLOAD_ID_SIZE + packet.data().len());
+        sealed.push(key_id);
+        sealed.extend_from_slice(&packet.serialize());
+        let tag = self.tag(key_id, &sealed)?;
+        sealed.extend_from_slice(&tag);
+        Ok(sealed)
+    }
+
+    /// Verifies the tag of a packet sealed by [`PacketAuthenticator::seal`], and deserializes it
+    pub fn open(&self, data: &[u8]) -> Result<EncodingPacket, RaptorQError> {
+        let tag_size = self.mac.tag_size();
+        if data.len() < 1 + PAYLOAD_ID_SIZE + tag_size {
+            return Err(RaptorQError::InsufficientData {
+                expected: 1 + PAYLOAD_ID_SIZE + tag_size,
+                actual: data.len(),
+            });
+        }
+        let (sealed, tag) = data.split_at(data.len() - tag_size);
+        if !constant_time_eq(tag, &self.tag(sealed[0], sealed)?) {
+            return Err(RaptorQError::AuthenticationFailed);
+        }
+        EncodingPacket::try_deserialize(&sealed[1..])
+    }
+
+    fn key(&self, key_id: u8) -> Option<&[u8]> {
+        self.keys
+            .iter()
+            .find(|(id, _)| *id == key_id)
+            .map(|(_, key)| key.as_slice())
+    }
+
+    fn tag(&self, key_id: u8, sealed: &[u8]) -> Result<Vec<u8>, RaptorQError> {
+        let key = self.key(key_id).ok_or(RaptorQError::UnknownKeyId(key_id))?;
+        let mut message = Vec::with_capacity(12 + sealed.len());
+        message.extend_from_slice(&self.config.serialize());
+        message.extend_from_slice(sealed);
+        Ok(self.mac.tag(key, &message))
+    }
+}
+
+// Keys are omitted, so that they don't end up in logs
+impl<M: PacketMac + fmt::Debug> fmt::Debug for PacketAuthenticator<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PacketAuthenticator")
+            .field("mac", &self.mac)
+            .field("config", &self.config)
+            .field(
+                "key_ids",
+                &self.keys.iter().map(|(id, _)| *id).collect::<Vec<u8>>(),
+            )
+            .field("signing_key_id", &self.signing_key_id)
+            .finish()
+    }
+}
+
+/// Wraps a [`Decoder`], verifying each sealed packet with a [`PacketAuthenticator`] before
+/// decoding it. Packets which fail authentication, or are otherwise invalid, are dropped and
+/// counted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthenticatedDecoder<M: PacketMac> {
+    decoder: Decoder,
+    authenticator: PacketAuthenticator<M>,
+    rejected_packets: u64,
+}
+
+impl<M: PacketMac> AuthenticatedDecoder<M> {
+    /// Creates a decoder for the object that `authenticator` was created for
+    pub fn new(authenticator: PacketAuthenticator<M>) -> AuthenticatedDecoder<M> {
+        AuthenticatedDecoder::with_decoder(Decoder::new(authenticator.config), authenticator)
+    }
+
+    pub fn with_decoder(
+        decoder: Decoder,
+        authenticator: PacketAuthenticator<M>,
+    ) -> AuthenticatedDecoder<M> {
+        AuthenticatedDecoder {
+            decoder,
+            authenticator,
+            rejected_packets: 0,
+        }
+    }
+
+    /// Authenticates and decodes a packet sealed by [`PacketAuthenticator::seal`]. Returns the
+    /// object once it has been decoded.
+    pub fn decode(&mut self, data: &[u8]) -> Option<Vec<u8>> {
+        let result = self
+            .authenticator
+            .open(data)
+            .and_then(|packet| self.decoder.try_decode(packet));
+        match result {
+            Ok(result) => result,
+            Err(_) => {
+                self.rejected_packets += 1;
+                None
+            }
+        }
+    }
+
+    /// Number of packets which were dropped, because they failed authentication or were invalid
+    pub fn rejected_packets(&self) -> u64 {
+        self.rejected_packets
+    }
+
+    pub fn authenticator(&self) -> &PacketAuthenticator<M> {
+        &self.authenticator
+    }
+
+    /// Allows keys to be rotated while decoding
+    pub fn authenticator_mut(&mut self) -> &mut PacketAuthenticator<M> {
+        &mut self.authenticator
+    }
+
+    pub fn decoder(&self) -> &Decoder {
+        &self.decoder
+    }
+
+    pub fn decoder_mut(&mut self) -> &mut Decoder {
+        &mut self.decoder
+    }
+
+    pub fn into_inner(self) -> Decoder {
+        self.decoder
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::vec::Vec;
+
+    #[cfg(not(feature = "python"))]
+    use crate::{AuthenticatedDecoder, Encoder};
+    use crate::{
+        EncodingPacket, HmacSha256, ObjectTransmissionInformation, PacketAuthenticator, PacketMac,
+        PayloadId, RaptorQError,
+    };
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn hmac_sha256_test_vectors() {
+        // RFC 4231, test cases 1, 2 and 6
+        assert_eq!(
+            hex(&HmacSha256.tag(&[0x0B; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex(&HmacSha256.tag(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&HmacSha256.tag(
+                &[0xAA; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn rejects_forged_packets() {
+        let config = ObjectTransmissionInformation::with_defaults(1000, 100);
+        let mut authenticator = PacketAuthenticator::new(HmacSha256, config);
+        assert_eq!(
+            authenticator.try_seal(&EncodingPacket::new(PayloadId::new(0, 0), vec![])),
+            Err(RaptorQError::NoSigningKey)
+        );
+        authenticator.add_key(1, b"secret");
+
+        let packet = EncodingPacket::new(PayloadId::new(0, 2), vec![3; 100]);
+        let sealed = authenticator.seal(&packet);
+        assert_eq!(sealed.len(), 1 + 104 + 32);
+        assert_eq!(authenticator.open(&sealed), Ok(packet.clone()));
+
+        for i in 1..sealed.len() {
+            let mut forged = sealed.clone();
+            forged[i] ^= 1 << rand::rng().random_range(0..8);
+            assert_eq!(
+                authenticator.open(&forged),
+                Err(RaptorQError::AuthenticationFailed)
+            );
+        }
+        let mut forged = sealed.clone();
+        forged[0] = 2;
+        assert_eq!(
+            authenticator.open(&forged),
+            Err(RaptorQError::UnknownKeyId(2))
+        );
+
+        // Packets for a different object, or sealed with a different key, are rejected
+        let other_config = ObjectTransmissionInformation::with_defaults(1001, 100);
+        let mut other = PacketAuthenticator::new(HmacSha256, other_config);
+        other.add_key(1, b"secret");
+        assert_eq!(other.open(&sealed), Err(RaptorQError::AuthenticationFailed));
+        let mut other = PacketAuthenticator::new(HmacSha256, config);
+        other.add_key(1, b"guess");
+        assert_eq!(other.open(&sealed), Err(RaptorQError::AuthenticationFailed));
+    }
+
+    #[test]
+    fn key_rotation() {
+        let config = ObjectTransmissionInformation::with_defaults(1000, 100);
+        let packet = EncodingPacket::new(PayloadId::new(0, 1), vec![7; 100]);
+        let mut authenticator = PacketAuthenticator::new(HmacSha256, config);
+        authenticator.add_key(1, b"old");
+        let old = authenticator.seal(&packet);
+
+        authenticator.add_key(2, b"new");
+        assert_eq!(authenticator.signing_key_id(), Some(1));
+        assert_eq!(
+            authenticator.set_signing_key(3),
+            Err(RaptorQError::UnknownKeyId(3))
+        );
+        authenticator.set_signing_key(2).unwrap();
+        let new = authenticator.seal(&packet);
+        assert_eq!(new[0], 2);
+        assert_eq!(authenticator.open(&old), Ok(packet.clone()));
+        assert_eq!(authenticator.open(&new), Ok(packet.clone()));
+
+        assert!(authenticator.remove_key(1));
+        assert!(!authenticator.remove_key(1));
+        assert_eq!(authenticator.open(&old), Err(RaptorQError::UnknownKeyId(1)));
+        assert_eq!(authenticator.open(&new), Ok(packet));
+        assert!(format!("{authenticator:?}").contains("key_ids: [2]"));
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn drops_forged_packets() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::with_defaults(data.len() as u64, 500);
+        let encoder = Encoder::new(&data, config);
+        let mut authenticator = PacketAuthenticator::new(HmacSha256, config);
+        authenticator.add_key(7, b"multicast group key");
+        let mut packets: Vec<Vec<u8>> = encoder
+            .get_encoded_packets(10)
+            .iter()
+            .map(|packet| authenticator.seal(packet))
+            .collect();
+        packets.shuffle(&mut rand::rng());
+        // Well-formed packets from someone without the key, which would otherwise poison a block
+        let mut attacker = PacketAuthenticator::new(HmacSha256, config);
+        attacker.add_key(7, b"guessed key");
+        for packet in encoder.get_encoded_packets(0).iter().take(5) {
+            let forged = EncodingPacket::new(packet.payload_id().clone(), vec![0; 500]);
+            packets.insert(0, attacker.seal(&forged));
+        }
+
+        let mut decoder = AuthenticatedDecoder::new(authenticator);
+        let mut result = None;
+        for packet in packets {
+            result = decoder.decode(&packet);
+            if result.is_some() {
+                break;
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+        assert_eq!(decoder.rejected_packets(), 5);
+    }
+}
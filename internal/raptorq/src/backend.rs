@@ -0,0 +1,206 @@
+//! Selection of the SIMD implementation used for symbol arithmetic.
+//!
+//! By default, the fastest backend supported by the CPU is detected on first use, and cached for
+//! the life of the process. A different backend can be pinned process-wide with [`pin`], for
+//! example to benchmark a specific implementation, or to check that all backends produce the
+//! same output.
+
+#[cfg(feature = "std")]
+use std::{fmt, sync::atomic::AtomicU8, sync::atomic::Ordering};
+
+#[cfg(not(feature = "std"))]
+use core::{fmt, sync::atomic::AtomicU8, sync::atomic::Ordering};
+
+use crate::error::RaptorQError;
+
+/// An implementation of the octet vector operations
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Portable implementation, which is supported everywhere
+    Scalar,
+    /// x86 SSSE3
+    Ssse3,
+    /// x86 AVX2 and BMI1
+    Avx2,
+    /// x86 AVX-512F and AVX-512BW, in addition to the AVX2 requirements
+    Avx512,
+    /// x86 GFNI, in addition to the AVX-512 requirements. Multiplies with `vgf2p8affineqb`
+    Gfni,
+    /// aarch64 NEON
+    Neon,
+    /// wasm32 SIMD128. Only available when compiled with the `simd128` target feature
+    Simd128,
+}
+
+const ALL_BACKENDS: [Backend; 7] = [
+    Backend::Scalar,
+    Backend::Ssse3,
+    Backend::Avx2,
+    Backend::Avx512,
+    Backend::Gfni,
+    Backend::Neon,
+    Backend::Simd128,
+];
+
+// 0 means that no backend has been detected, or pinned. Otherwise, the index into ALL_BACKENDS + 1
+static DETECTED: AtomicU8 = AtomicU8::new(0);
+static PINNED: AtomicU8 = AtomicU8::new(0);
+
+impl Backend {
+    /// Returns every backend, whether or not it is supported on this machine
+    pub fn all() -> &'static [Backend] {
+        &ALL_BACKENDS
+    }
+
+    /// Returns the backends which can be used on this machine, from slowest to fastest
+    pub fn supported() -> impl Iterator<Item = Backend> {
+        ALL_BACKENDS
+            .iter()
+            .copied()
+            .filter(|backend| backend.is_supported())
+    }
+
+    /// Looks up a backend by its [`Backend::name`]
+    pub fn from_name(name: &str) -> Option<Backend> {
+        ALL_BACKENDS
+            .iter()
+            .copied()
+            .find(|backend| backend.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Scalar => "scalar",
+            Backend::Ssse3 => "ssse3",
+            Backend::Avx2 => "avx2",
+            Backend::Avx512 => "avx512",
+            Backend::Gfni => "gfni",
+            Backend::Neon => "neon",
+            Backend::Simd128 => "simd128",
+        }
+    }
+
+    /// Returns true if the CPU, and the features this crate was compiled with, support the backend.
+    /// The SIMD backends require the `std` feature, except for SIMD128 which is detected at
+    /// compile time.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+            Backend::Ssse3 => is_x86_feature_detected!("ssse3"),
+            #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+            Backend::Avx2 => is_x86_feature_detected!("avx2") && is_x86_feature_detected!("bmi1"),
+            #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+            Backend::Avx512 => {
+                Backend::Avx2.is_supported()
+                    && is_x86_feature_detected!("avx512f")
+                    && is_x86_feature_detected!("avx512bw")
+            }
+            #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+            Backend::Gfni => Backend::Avx512.is_supported() && is_x86_feature_detected!("gfni"),
+            #[cfg(all(target_arch = "aarch64", feature = "std"))]
+            Backend::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+            Backend::Simd128 => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    fn encode(self) -> u8 {
+        ALL_BACKENDS
+            .iter()
+            .position(|backend| *backend == self)
+            .unwrap() as u8
+            + 1
+    }
+
+    fn decode(value: u8) -> Option<Backend> {
+        match value {
+            0 => None,
+            _ => Some(ALL_BACKENDS[value as usize - 1]),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Returns the fastest backend supported on this machine. Detection only runs once.
+pub fn detected() -> Backend {
+    if let Some(backend) = Backend::decode(DETECTED.load(Ordering::Relaxed)) {
+        return backend;
+    }
+    // Concurrent callers may both run detection, but always agree on the result
+    let backend = Backend::supported().last().unwrap();
+    DETECTED.store(backend.encode(), Ordering::Relaxed);
+    backend
+}
+
+/// Returns the backend used for symbol arithmetic: the pinned backend if there is one, and
+/// otherwise the detected backend
+pub fn active() -> Backend {
+    Backend::decode(PINNED.load(Ordering::Relaxed)).unwrap_or_else(detected)
+}
+
+/// Returns the pinned backend, if any
+pub fn pinned() -> Option<Backend> {
+    Backend::decode(PINNED.load(Ordering::Relaxed))
+}
+
+/// Forces every subsequent operation, on every thread, to use `backend`.
+///
+/// Returns an error, and leaves the active backend unchanged, if `backend` is not supported on
+/// this machine.
+pub fn pin(backend: Backend) -> Result<(), RaptorQError> {
+    if !backend.is_supported() {
+        return Err(RaptorQError::UnsupportedBackend(backend));
+    }
+    PINNED.store(backend.encode(), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Removes any pinned backend, reverting to the detected backend
+pub fn unpin() {
+    PINNED.store(0, Ordering::Relaxed);
+}
+
+// The pinned backend is shared by every thread, so tests which pin one must hold this lock, to
+// keep other tests from changing it while they run
+#[cfg(feature = "std")]
+#[cfg(test)]
+pub(crate) fn lock_pinned() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use crate::backend::{self, Backend};
+
+    #[test]
+    fn detection() {
+        assert!(Backend::Scalar.is_supported());
+        assert!(backend::detected().is_supported());
+        assert_eq!(backend::detected(), backend::detected());
+        for backend in Backend::all() {
+            assert_eq!(Backend::from_name(backend.name()), Some(*backend));
+        }
+        assert_eq!(Backend::from_name("avx1024"), None);
+    }
+
+    #[test]
+    fn unsupported_backend() {
+        let _pinned = backend::lock_pinned();
+        for backend in Backend::all() {
+            if !backend.is_supported() {
+                assert!(backend::pin(*backend).is_err());
+            }
+        }
+    }
+}
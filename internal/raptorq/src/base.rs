@@ -7,6 +7,7 @@ use core::cmp::min;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+use crate::error::RaptorQError;
 use crate::rng::rand;
 use crate::systematic_constants::{
     MAX_SOURCE_SYMBOLS_PER_BLOCK, SYSTEMATIC_INDICES_AND_PARAMETERS,
@@ -15,6 +16,9 @@ use crate::util::int_div_ceil;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
+// Length of a serialized PayloadId
+pub(crate) const PAYLOAD_ID_SIZE: usize = 4;
+
 // As defined in section 3.2
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
@@ -25,12 +29,21 @@ pub struct PayloadId {
 
 impl PayloadId {
     pub fn new(source_block_number: u8, encoding_symbol_id: u32) -> PayloadId {
+        PayloadId::try_new(source_block_number, encoding_symbol_id).unwrap()
+    }
+
+    pub fn try_new(
+        source_block_number: u8,
+        encoding_symbol_id: u32,
+    ) -> Result<PayloadId, RaptorQError> {
         // Encoding Symbol ID must be a 24-bit unsigned int
-        assert!(encoding_symbol_id < 16777216);
-        PayloadId {
+        if encoding_symbol_id >= 16777216 {
+            return Err(RaptorQError::InvalidEncodingSymbolId(encoding_symbol_id));
+        }
+        Ok(PayloadId {
             source_block_number,
             encoding_symbol_id,
-        }
+        })
     }
 
     #[allow(clippy::trivially_copy_pass_by_ref)]
@@ -41,6 +54,17 @@ impl PayloadId {
         }
     }
 
+    /// Deserializes a payload ID from the first 4 bytes of `data`, which may be of any length.
+    pub fn try_deserialize(data: &[u8]) -> Result<PayloadId, RaptorQError> {
+        match data.first_chunk::<4>() {
+            Some(header) => Ok(PayloadId::deserialize(header)),
+            None => Err(RaptorQError::InsufficientData {
+                expected: 4,
+                actual: data.len(),
+            }),
+        }
+    }
+
     pub fn serialize(&self) -> [u8; 4] {
         [
             self.source_block_number,
@@ -82,6 +106,15 @@ impl EncodingPacket {
         }
     }
 
+    /// Like [`EncodingPacket::deserialize`], but returns an error instead of panicking if
+    /// `data` is too short to contain a payload ID.
+    pub fn try_deserialize(data: &[u8]) -> Result<EncodingPacket, RaptorQError> {
+        Ok(EncodingPacket {
+            payload_id: PayloadId::try_deserialize(data)?,
+            data: Vec::from(&data[4..]),
+        })
+    }
+
     pub fn serialize(&self) -> Vec<u8> {
         let mut serialized = Vec::with_capacity(4 + self.data.len());
         serialized.extend_from_slice(&self.payload_id.serialize());
@@ -105,6 +138,186 @@ impl EncodingPacket {
     }
 }
 
+// Maximum transfer length, F. See errata (https://www.rfc-editor.org/errata/eid5548)
+const MAX_TRANSFER_LENGTH: u64 = 942574504275;
+
+/// FEC Encoding ID assigned to RaptorQ. See section 3.3.1
+pub const FEC_ENCODING_ID: u8 = 6;
+
+pub(crate) const COMMON_OTI_SIZE: usize = 8;
+pub(crate) const SCHEME_SPECIFIC_OTI_SIZE: usize = 4;
+
+/// Common FEC Object Transmission Information. See section 3.3.2
+///
+/// Carries the transfer length (F) and symbol size (T). The FEC Encoding ID is not part of this
+/// encoding, since protocols such as FLUTE and SDP carry it separately.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct CommonOti {
+    transfer_length: u64, // Limited to u40
+    symbol_size: u16,
+}
+
+impl CommonOti {
+    pub fn new(transfer_length: u64, symbol_size: u16) -> CommonOti {
+        CommonOti::try_new(transfer_length, symbol_size).unwrap()
+    }
+
+    pub fn try_new(transfer_length: u64, symbol_size: u16) -> Result<CommonOti, RaptorQError> {
+        if transfer_length > MAX_TRANSFER_LENGTH {
+            return Err(RaptorQError::TransferLengthTooLarge(transfer_length));
+        }
+        Ok(CommonOti {
+            transfer_length,
+            symbol_size,
+        })
+    }
+
+    pub fn deserialize(data: &[u8; 8]) -> CommonOti {
+        CommonOti {
+            transfer_length: ((data[0] as u64) << 32)
+                + ((data[1] as u64) << 24)
+                + ((data[2] as u64) << 16)
+                + ((data[3] as u64) << 8)
+                + (data[4] as u64),
+            symbol_size: ((data[6] as u16) << 8) + data[7] as u16,
+        }
+    }
+
+    /// Deserializes a Common FEC OTI received from an untrusted source.
+    ///
+    /// Rejects a non-zero reserved field, a zero symbol size, or a transfer length above the
+    /// maximum supported by RFC 6330. For compatibility with OTIs written by older versions of
+    /// the JavaScript wrapper, which stored the FEC Encoding ID in the reserved field, a reserved
+    /// field equal to [`FEC_ENCODING_ID`] is also accepted.
+    pub fn try_deserialize(data: &[u8]) -> Result<CommonOti, RaptorQError> {
+        let data = data
+            .first_chunk::<COMMON_OTI_SIZE>()
+            .ok_or(RaptorQError::InsufficientData {
+                expected: COMMON_OTI_SIZE,
+                actual: data.len(),
+            })?;
+        if data[5] != 0 && data[5] != FEC_ENCODING_ID {
+            return Err(RaptorQError::InvalidReservedField(data[5]));
+        }
+        let oti = CommonOti::deserialize(data);
+        if oti.symbol_size == 0 {
+            return Err(RaptorQError::InvalidSymbolSize(oti.symbol_size));
+        }
+        CommonOti::try_new(oti.transfer_length, oti.symbol_size)
+    }
+
+    /// Same as [`CommonOti::try_deserialize`], for protocols which carry the FEC Encoding ID
+    /// alongside the OTI, such as the LCT codepoint or the FDT's `FEC-OTI-FEC-Encoding-ID`.
+    /// Rejects any FEC Encoding ID other than [`FEC_ENCODING_ID`].
+    pub fn try_deserialize_with_encoding_id(
+        fec_encoding_id: u8,
+        data: &[u8],
+    ) -> Result<CommonOti, RaptorQError> {
+        if fec_encoding_id != FEC_ENCODING_ID {
+            return Err(RaptorQError::UnsupportedFecEncodingId(fec_encoding_id));
+        }
+        CommonOti::try_deserialize(data)
+    }
+
+    pub fn serialize(&self) -> [u8; 8] {
+        [
+            ((self.transfer_length >> 32) & 0xFF) as u8,
+            ((self.transfer_length >> 24) & 0xFF) as u8,
+            ((self.transfer_length >> 16) & 0xFF) as u8,
+            ((self.transfer_length >> 8) & 0xFF) as u8,
+            (self.transfer_length & 0xFF) as u8,
+            0, // Reserved
+            (self.symbol_size >> 8) as u8,
+            (self.symbol_size & 0xFF) as u8,
+        ]
+    }
+
+    pub fn fec_encoding_id(&self) -> u8 {
+        FEC_ENCODING_ID
+    }
+
+    pub fn transfer_length(&self) -> u64 {
+        self.transfer_length
+    }
+
+    pub fn symbol_size(&self) -> u16 {
+        self.symbol_size
+    }
+}
+
+/// Scheme-Specific FEC Object Transmission Information. See section 3.3.3
+///
+/// Carries the number of source blocks (Z), number of sub-blocks (N) and symbol alignment (Al).
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct SchemeSpecificOti {
+    num_source_blocks: u8,
+    num_sub_blocks: u16,
+    symbol_alignment: u8,
+}
+
+impl SchemeSpecificOti {
+    pub fn new(source_blocks: u8, sub_blocks: u16, alignment: u8) -> SchemeSpecificOti {
+        SchemeSpecificOti {
+            num_source_blocks: source_blocks,
+            num_sub_blocks: sub_blocks,
+            symbol_alignment: alignment,
+        }
+    }
+
+    pub fn deserialize(data: &[u8; 4]) -> SchemeSpecificOti {
+        SchemeSpecificOti {
+            num_source_blocks: data[0],
+            num_sub_blocks: ((data[1] as u16) << 8) + data[2] as u16,
+            symbol_alignment: data[3],
+        }
+    }
+
+    /// Deserializes a Scheme-Specific FEC OTI received from an untrusted source.
+    ///
+    /// Rejects a zero number of source blocks or sub-blocks. The symbol alignment is validated
+    /// against the symbol size when combined with a [`CommonOti`], by
+    /// [`ObjectTransmissionInformation::from_parts`].
+    pub fn try_deserialize(data: &[u8]) -> Result<SchemeSpecificOti, RaptorQError> {
+        let data = data.first_chunk::<SCHEME_SPECIFIC_OTI_SIZE>().ok_or(
+            RaptorQError::InsufficientData {
+                expected: SCHEME_SPECIFIC_OTI_SIZE,
+                actual: data.len(),
+            },
+        )?;
+        let oti = SchemeSpecificOti::deserialize(data);
+        if oti.num_source_blocks == 0 {
+            return Err(RaptorQError::InvalidSourceBlockCount(oti.num_source_blocks));
+        }
+        if oti.num_sub_blocks == 0 {
+            return Err(RaptorQError::InvalidSubBlockCount(oti.num_sub_blocks));
+        }
+        Ok(oti)
+    }
+
+    pub fn serialize(&self) -> [u8; 4] {
+        [
+            self.num_source_blocks,
+            (self.num_sub_blocks >> 8) as u8,
+            (self.num_sub_blocks & 0xFF) as u8,
+            self.symbol_alignment,
+        ]
+    }
+
+    pub fn source_blocks(&self) -> u8 {
+        self.num_source_blocks
+    }
+
+    pub fn sub_blocks(&self) -> u16 {
+        self.num_sub_blocks
+    }
+
+    pub fn symbol_alignment(&self) -> u8 {
+        self.symbol_alignment
+    }
+}
+
 // As defined in section 3.3.2 and 3.3.3
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
@@ -124,57 +337,120 @@ impl ObjectTransmissionInformation {
         sub_blocks: u16,
         alignment: u8,
     ) -> ObjectTransmissionInformation {
+        ObjectTransmissionInformation::try_new(
+            transfer_length,
+            symbol_size,
+            source_blocks,
+            sub_blocks,
+            alignment,
+        )
+        .unwrap()
+    }
+
+    pub fn try_new(
+        transfer_length: u64,
+        symbol_size: u16,
+        source_blocks: u8,
+        sub_blocks: u16,
+        alignment: u8,
+    ) -> Result<ObjectTransmissionInformation, RaptorQError> {
         // See errata (https://www.rfc-editor.org/errata/eid5548)
-        assert!(transfer_length <= 942574504275);
-        assert_eq!(symbol_size % alignment as u16, 0);
+        if transfer_length > MAX_TRANSFER_LENGTH {
+            return Err(RaptorQError::TransferLengthTooLarge(transfer_length));
+        }
+        if alignment == 0 || !symbol_size.is_multiple_of(alignment as u16) {
+            return Err(RaptorQError::InvalidSymbolAlignment {
+                symbol_size,
+                alignment,
+            });
+        }
         // See section 4.4.1.2. "These parameters MUST be set so that ceil(ceil(F/T)/Z) <= K'_max."
-
         if (symbol_size != 0) && (source_blocks != 0) {
-            let symbols_required = int_div_ceil(
-                int_div_ceil(transfer_length, symbol_size as u64) as u64,
-                source_blocks as u64,
-            );
-            assert!((symbols_required) <= MAX_SOURCE_SYMBOLS_PER_BLOCK);
+            let symbols_required = transfer_length
+                .div_ceil(symbol_size as u64)
+                .div_ceil(source_blocks as u64);
+            if symbols_required > MAX_SOURCE_SYMBOLS_PER_BLOCK as u64 {
+                return Err(RaptorQError::TooManySourceSymbols(symbols_required));
+            }
         }
 
-        ObjectTransmissionInformation {
+        Ok(ObjectTransmissionInformation {
             transfer_length,
             symbol_size,
             num_source_blocks: source_blocks,
             num_sub_blocks: sub_blocks,
             symbol_alignment: alignment,
-        }
+        })
+    }
+
+    /// Combines the Common and Scheme-Specific FEC OTI, such as when they are carried separately
+    /// by an FDT or SDP description, validating them as [`ObjectTransmissionInformation::try_new`]
+    /// does.
+    pub fn from_parts(
+        common: &CommonOti,
+        scheme_specific: &SchemeSpecificOti,
+    ) -> Result<ObjectTransmissionInformation, RaptorQError> {
+        ObjectTransmissionInformation::try_new(
+            common.transfer_length,
+            common.symbol_size,
+            scheme_specific.num_source_blocks,
+            scheme_specific.num_sub_blocks,
+            scheme_specific.symbol_alignment,
+        )
     }
 
     pub fn deserialize(data: &[u8; 12]) -> ObjectTransmissionInformation {
+        let (common, scheme_specific) = data.split_at(COMMON_OTI_SIZE);
+        let common = CommonOti::deserialize(common.try_into().unwrap());
+        let scheme_specific = SchemeSpecificOti::deserialize(scheme_specific.try_into().unwrap());
         ObjectTransmissionInformation {
-            transfer_length: ((data[0] as u64) << 32)
-                + ((data[1] as u64) << 24)
-                + ((data[2] as u64) << 16)
-                + ((data[3] as u64) << 8)
-                + (data[4] as u64),
-            symbol_size: ((data[6] as u16) << 8) + data[7] as u16,
-            num_source_blocks: data[8],
-            num_sub_blocks: ((data[9] as u16) << 8) + data[10] as u16,
-            symbol_alignment: data[11],
+            transfer_length: common.transfer_length,
+            symbol_size: common.symbol_size,
+            num_source_blocks: scheme_specific.num_source_blocks,
+            num_sub_blocks: scheme_specific.num_sub_blocks,
+            symbol_alignment: scheme_specific.symbol_alignment,
         }
     }
 
+    /// Deserializes an OTI received from an untrusted source.
+    ///
+    /// The OTI is the Common FEC OTI followed by the Scheme-Specific FEC OTI, and each part is
+    /// validated as by [`CommonOti::try_deserialize`] and [`SchemeSpecificOti::try_deserialize`],
+    /// before being combined by [`ObjectTransmissionInformation::from_parts`].
+    pub fn try_deserialize(data: &[u8]) -> Result<ObjectTransmissionInformation, RaptorQError> {
+        let data = data
+            .first_chunk::<12>()
+            .ok_or(RaptorQError::InsufficientData {
+                expected: 12,
+                actual: data.len(),
+            })?;
+        let (common, scheme_specific) = data.split_at(COMMON_OTI_SIZE);
+        let common = CommonOti::try_deserialize(common)?;
+        let scheme_specific = SchemeSpecificOti::try_deserialize(scheme_specific)?;
+        ObjectTransmissionInformation::from_parts(&common, &scheme_specific)
+    }
+
     pub fn serialize(&self) -> [u8; 12] {
-        [
-            ((self.transfer_length >> 32) & 0xFF) as u8,
-            ((self.transfer_length >> 24) & 0xFF) as u8,
-            ((self.transfer_length >> 16) & 0xFF) as u8,
-            ((self.transfer_length >> 8) & 0xFF) as u8,
-            (self.transfer_length & 0xFF) as u8,
-            0, // Reserved
-            (self.symbol_size >> 8) as u8,
-            (self.symbol_size & 0xFF) as u8,
-            self.num_source_blocks,
-            (self.num_sub_blocks >> 8) as u8,
-            (self.num_sub_blocks & 0xFF) as u8,
-            self.symbol_alignment,
-        ]
+        let mut data = [0; 12];
+        let (common, scheme_specific) = data.split_at_mut(COMMON_OTI_SIZE);
+        common.copy_from_slice(&self.common().serialize());
+        scheme_specific.copy_from_slice(&self.scheme_specific().serialize());
+        data
+    }
+
+    pub fn common(&self) -> CommonOti {
+        CommonOti {
+            transfer_length: self.transfer_length,
+            symbol_size: self.symbol_size,
+        }
+    }
+
+    pub fn scheme_specific(&self) -> SchemeSpecificOti {
+        SchemeSpecificOti {
+            num_source_blocks: self.num_source_blocks,
+            num_sub_blocks: self.num_sub_blocks,
+            symbol_alignment: self.symbol_alignment,
+        }
     }
 
     pub fn transfer_length(&self) -> u64 {
@@ -243,6 +519,93 @@ impl ObjectTransmissionInformation {
         }
     }
 
+    // Derives the source block and sub-block partitioning, as recommended in section 4.3. Unlike
+    // generate_encoding_parameters(), the alignment and bounds are chosen by the caller, and an
+    // error is returned if they cannot be met. The symbol size is the largest multiple of the
+    // alignment that fits in a packet, and min_sub_symbol_size is SS * Al
+    pub(crate) fn try_generate_encoding_parameters(
+        transfer_length: u64,
+        max_packet_size: u16,
+        alignment: u8,
+        min_sub_symbol_size: u16,
+        decoder_memory_requirement: u64,
+        max_source_blocks: u8,
+    ) -> Result<ObjectTransmissionInformation, RaptorQError> {
+        if transfer_length > MAX_TRANSFER_LENGTH {
+            return Err(RaptorQError::TransferLengthTooLarge(transfer_length));
+        }
+        if transfer_length == 0 {
+            return Err(RaptorQError::InvalidEncodingParameters(
+                "transfer length must be non-zero",
+            ));
+        }
+        if alignment == 0 {
+            return Err(RaptorQError::InvalidSymbolAlignment {
+                symbol_size: max_packet_size,
+                alignment,
+            });
+        }
+        if max_packet_size < alignment as u16 {
+            return Err(RaptorQError::InvalidEncodingParameters(
+                "maximum packet size is smaller than the symbol alignment",
+            ));
+        }
+        let symbol_size = max_packet_size - (max_packet_size % alignment as u16);
+        if min_sub_symbol_size == 0 || !min_sub_symbol_size.is_multiple_of(alignment as u16) {
+            return Err(RaptorQError::InvalidEncodingParameters(
+                "minimum sub-symbol size must be a non-zero multiple of the symbol alignment",
+            ));
+        }
+
+        let kt = int_div_ceil(transfer_length, symbol_size as u64);
+
+        let n_max = (symbol_size / min_sub_symbol_size) as u32;
+        if n_max == 0 {
+            return Err(RaptorQError::InvalidEncodingParameters(
+                "minimum sub-symbol size is larger than the symbol size",
+            ));
+        }
+
+        // KL(n): the largest K' for which a sub-block of n sub-symbols fits in the decoder's
+        // working memory
+        let kl = |n: u32| -> Option<u32> {
+            let x = int_div_ceil(symbol_size as u64, alignment as u64 * n as u64);
+            SYSTEMATIC_INDICES_AND_PARAMETERS
+                .iter()
+                .rev()
+                .map(|&(kprime, _, _, _, _)| kprime)
+                .find(|&kprime| {
+                    kprime as u64 <= decoder_memory_requirement / (alignment as u64 * x as u64)
+                })
+        };
+
+        // KL(n) does not decrease with n, so sub-blocking as much as allowed gives the largest
+        // blocks
+        let largest_block = kl(n_max).ok_or(RaptorQError::InvalidEncodingParameters(
+            "decoder memory requirement is too small for the smallest source block",
+        ))?;
+        let num_source_blocks = int_div_ceil(kt as u64, largest_block as u64);
+        if num_source_blocks > max_source_blocks as u32 {
+            return Err(RaptorQError::TooManySourceBlocks {
+                required: num_source_blocks,
+                limit: max_source_blocks,
+            });
+        }
+
+        let symbols_per_block = int_div_ceil(kt as u64, num_source_blocks as u64);
+        let n = (1..=n_max)
+            .find(|&n| kl(n).is_some_and(|kl| symbols_per_block <= kl))
+            .unwrap();
+
+        Ok(ObjectTransmissionInformation {
+            transfer_length,
+            symbol_size,
+            num_source_blocks: num_source_blocks as u8,
+            num_sub_blocks: n as u16,
+            symbol_alignment: alignment,
+        })
+    }
+
     pub fn with_defaults(
         transfer_length: u64,
         max_packet_size: u16,
@@ -328,7 +691,10 @@ pub fn intermediate_tuple(
 
 #[cfg(test)]
 mod tests {
-    use crate::{EncodingPacket, ObjectTransmissionInformation, PayloadId};
+    use crate::{
+        CommonOti, EncodingPacket, FEC_ENCODING_ID, ObjectTransmissionInformation, PayloadId,
+        RaptorQError, SchemeSpecificOti,
+    };
     use rand::Rng;
 
     #[test]
@@ -366,4 +732,201 @@ mod tests {
         let deserialized = ObjectTransmissionInformation::deserialize(&oti.serialize());
         assert_eq!(deserialized, oti);
     }
+
+    #[test]
+    fn payload_id_out_of_range() {
+        assert_eq!(
+            PayloadId::try_new(0, 256 * 256 * 256),
+            Err(RaptorQError::InvalidEncodingSymbolId(256 * 256 * 256))
+        );
+    }
+
+    #[test]
+    fn truncated_encoding_packet() {
+        for len in 0..4 {
+            assert_eq!(
+                EncodingPacket::try_deserialize(&vec![0; len]),
+                Err(RaptorQError::InsufficientData {
+                    expected: 4,
+                    actual: len
+                })
+            );
+        }
+        let packet = EncodingPacket::try_deserialize(&[1, 0, 0, 2]).unwrap();
+        assert_eq!(packet.payload_id(), &PayloadId::new(1, 2));
+        assert!(packet.data().is_empty());
+    }
+
+    #[test]
+    fn invalid_oti() {
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(942574504276, 65535, 255, 1, 1),
+            Err(RaptorQError::TransferLengthTooLarge(942574504276))
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1024, 10, 1, 1, 0),
+            Err(RaptorQError::InvalidSymbolAlignment {
+                symbol_size: 10,
+                alignment: 0
+            })
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1024, 10, 1, 1, 4),
+            Err(RaptorQError::InvalidSymbolAlignment {
+                symbol_size: 10,
+                alignment: 4
+            })
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(942574504275, 1, 1, 1, 1),
+            Err(RaptorQError::TooManySourceSymbols(942574504275))
+        );
+    }
+
+    #[test]
+    fn oti_untrusted_deserialization() {
+        let oti = ObjectTransmissionInformation::new(1024, 64, 2, 1, 8);
+        let serialized = oti.serialize();
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(&serialized),
+            Ok(oti)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(&serialized[..11]),
+            Err(RaptorQError::InsufficientData {
+                expected: 12,
+                actual: 11
+            })
+        );
+
+        let mut zero_blocks = serialized;
+        zero_blocks[8] = 0;
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(&zero_blocks),
+            Err(RaptorQError::InvalidSourceBlockCount(0))
+        );
+
+        let mut zero_alignment = serialized;
+        zero_alignment[11] = 0;
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(&zero_alignment),
+            Err(RaptorQError::InvalidSymbolAlignment {
+                symbol_size: 64,
+                alignment: 0
+            })
+        );
+    }
+
+    #[test]
+    fn oti_parts() {
+        let oti = ObjectTransmissionInformation::new(942574504275, 65535, 255, 258, 5);
+        let common = oti.common();
+        let scheme_specific = oti.scheme_specific();
+        assert_eq!(
+            common.serialize(),
+            [0xDB, 0x75, 0xD1, 0x89, 0x53, 0, 0xFF, 0xFF]
+        );
+        assert_eq!(scheme_specific.serialize(), [0xFF, 0x01, 0x02, 0x05]);
+        assert_eq!(oti.serialize()[..8], common.serialize());
+        assert_eq!(oti.serialize()[8..], scheme_specific.serialize());
+        assert_eq!(common.fec_encoding_id(), FEC_ENCODING_ID);
+
+        assert_eq!(CommonOti::try_deserialize(&common.serialize()), Ok(common));
+        assert_eq!(
+            SchemeSpecificOti::try_deserialize(&scheme_specific.serialize()),
+            Ok(scheme_specific)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::from_parts(&common, &scheme_specific),
+            Ok(oti)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::from_parts(
+                &CommonOti::new(1024, 10),
+                &SchemeSpecificOti::new(1, 1, 4)
+            ),
+            Err(RaptorQError::InvalidSymbolAlignment {
+                symbol_size: 10,
+                alignment: 4
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_oti_parts() {
+        let common = CommonOti::new(1024, 64).serialize();
+        assert_eq!(
+            CommonOti::try_deserialize(&common[..7]),
+            Err(RaptorQError::InsufficientData {
+                expected: 8,
+                actual: 7
+            })
+        );
+        let mut reserved = common;
+        reserved[5] = 7;
+        assert_eq!(
+            CommonOti::try_deserialize(&reserved),
+            Err(RaptorQError::InvalidReservedField(7))
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(
+                &[&reserved[..], &SchemeSpecificOti::new(1, 1, 8).serialize()].concat()
+            ),
+            Err(RaptorQError::InvalidReservedField(7))
+        );
+        let mut zero_symbol_size = common;
+        zero_symbol_size[7] = 0;
+        assert_eq!(
+            CommonOti::try_deserialize(&zero_symbol_size),
+            Err(RaptorQError::InvalidSymbolSize(0))
+        );
+        assert_eq!(
+            CommonOti::try_deserialize(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 1]),
+            Err(RaptorQError::TransferLengthTooLarge(0xFF_FFFF_FFFF))
+        );
+
+        assert_eq!(
+            CommonOti::try_deserialize_with_encoding_id(FEC_ENCODING_ID, &common),
+            Ok(CommonOti::new(1024, 64))
+        );
+        assert_eq!(
+            CommonOti::try_deserialize_with_encoding_id(0, &common),
+            Err(RaptorQError::UnsupportedFecEncodingId(0))
+        );
+
+        assert_eq!(
+            SchemeSpecificOti::try_deserialize(&[1, 0, 1]),
+            Err(RaptorQError::InsufficientData {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            SchemeSpecificOti::try_deserialize(&[0, 0, 1, 8]),
+            Err(RaptorQError::InvalidSourceBlockCount(0))
+        );
+        assert_eq!(
+            SchemeSpecificOti::try_deserialize(&[1, 0, 0, 8]),
+            Err(RaptorQError::InvalidSubBlockCount(0))
+        );
+    }
+
+    // Older versions of the JavaScript wrapper wrote the FEC Encoding ID into the reserved field
+    #[test]
+    fn legacy_oti() {
+        let oti = ObjectTransmissionInformation::new(1024, 64, 2, 1, 8);
+        let mut legacy = oti.serialize();
+        legacy[5] = FEC_ENCODING_ID;
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(&legacy),
+            Ok(oti)
+        );
+        assert_eq!(CommonOti::try_deserialize(&legacy[..8]), Ok(oti.common()));
+        assert_eq!(
+            CommonOti::try_deserialize_with_encoding_id(FEC_ENCODING_ID, &legacy[..8]),
+            Ok(oti.common())
+        );
+        // It's always written as zero
+        assert_eq!(oti.serialize()[5], 0);
+    }
 }
@@ -0,0 +1,146 @@
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::error::RaptorQError;
+
+// Helpers for the crate's compact, versioned binary formats. Each starts with a four byte magic
+// and a version byte, and stores integers as LEB128 varints
+
+pub fn write_header(out: &mut Vec<u8>, magic: &[u8; 4], version: u8) {
+    out.extend_from_slice(magic);
+    out.push(version);
+}
+
+pub fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    // Constructs the error for malformed input
+    invalid: fn(&'static str) -> RaptorQError,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8], invalid: fn(&'static str) -> RaptorQError) -> Reader<'a> {
+        Reader {
+            data,
+            position: 0,
+            invalid,
+        }
+    }
+
+    pub fn invalid(&self, reason: &'static str) -> RaptorQError {
+        (self.invalid)(reason)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    pub fn bytes(&mut self, length: usize) -> Result<&'a [u8], RaptorQError> {
+        if self.remaining() < length {
+            return Err(RaptorQError::InsufficientData {
+                expected: self.position + length,
+                actual: self.data.len(),
+            });
+        }
+        let bytes = &self.data[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+
+    pub fn byte(&mut self) -> Result<u8, RaptorQError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn varint(&mut self) -> Result<u64, RaptorQError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            // Only the lowest bit of the tenth byte fits in a u64
+            if shift == 63 && byte > 1 {
+                return Err(self.invalid("varint overflows 64 bits"));
+            }
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.invalid("varint is too long"))
+    }
+
+    // Reads a count of items which each take at least `min_item_size` bytes, rejecting counts
+    // that could not fit in the remaining input, so that they are safe to allocate for
+    pub fn count(&mut self, min_item_size: usize) -> Result<usize, RaptorQError> {
+        let count = self.varint()?;
+        if count > (self.remaining() / min_item_size.max(1)) as u64 {
+            return Err(self.invalid("count out of range"));
+        }
+        Ok(count as usize)
+    }
+
+    pub fn header(&mut self, magic: &[u8; 4], version: u8) -> Result<(), RaptorQError> {
+        if self.bytes(4)? != magic {
+            return Err(self.invalid("unrecognized format"));
+        }
+        if self.byte()? != version {
+            return Err(self.invalid("unsupported version"));
+        }
+        Ok(())
+    }
+
+    pub fn finish(&self) -> Result<(), RaptorQError> {
+        if self.remaining() != 0 {
+            return Err(self.invalid("trailing data"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::binary_format::{Reader, write_varint};
+    use crate::error::RaptorQError;
+
+    #[test]
+    fn varint_round_trip() {
+        for value in [0, 1, 0x7F, 0x80, u32::MAX as u64, u64::MAX - 1, u64::MAX] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+            let mut reader = Reader::new(&out, RaptorQError::InvalidPlan);
+            assert_eq!(reader.varint(), Ok(value));
+            assert_eq!(reader.finish(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn malformed_varint() {
+        // Sets bits above the 64th
+        let mut overflow = vec![0xFF; 9];
+        overflow.push(0x02);
+        assert_eq!(
+            Reader::new(&overflow, RaptorQError::InvalidPlan).varint(),
+            Err(RaptorQError::InvalidPlan("varint overflows 64 bits"))
+        );
+
+        // Continues past the tenth byte
+        let mut too_long = vec![0x80; 10];
+        too_long.push(0x00);
+        assert_eq!(
+            Reader::new(&too_long, RaptorQError::InvalidPlan).varint(),
+            Err(RaptorQError::InvalidPlan("varint overflows 64 bits"))
+        );
+    }
+}
@@ -0,0 +1,225 @@
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::base::{EncodingPacket, ObjectTransmissionInformation, PayloadId};
+use crate::binary_format::{Reader, write_header, write_varint};
+use crate::encoder::object_block_offsets;
+use crate::error::RaptorQError;
+
+// Binary format of a decoder checkpoint:
+//   "RQCP", version (1 byte), OTI (12 bytes), then the state of each source block, in order:
+//   PARTIAL: symbol count (varint), then each symbol as its ESI (varint) and payload
+//   COMPLETE: the decoded block, including any padding
+//   WRITTEN: nothing. The block was decoded and stored elsewhere, such as in the sink of a
+//            StreamingDecoder
+const MAGIC: &[u8; 4] = b"RQCP";
+const FORMAT_VERSION: u8 = 1;
+
+const PARTIAL: u8 = 0;
+const COMPLETE: u8 = 1;
+const WRITTEN: u8 = 2;
+
+pub enum BlockCheckpoint {
+    Partial(Vec<EncodingPacket>),
+    Complete(Vec<u8>),
+    Written,
+}
+
+pub struct Checkpoint {
+    pub config: ObjectTransmissionInformation,
+    pub blocks: Vec<BlockCheckpoint>,
+}
+
+pub struct CheckpointWriter {
+    out: Vec<u8>,
+}
+
+impl CheckpointWriter {
+    pub fn new(config: &ObjectTransmissionInformation) -> CheckpointWriter {
+        let mut out = vec![];
+        write_header(&mut out, MAGIC, FORMAT_VERSION);
+        out.extend_from_slice(&config.serialize());
+        CheckpointWriter { out }
+    }
+
+    // Blocks must be written in order of source block number
+    pub fn partial_block<'a>(
+        &mut self,
+        symbol_count: u32,
+        symbols: impl Iterator<Item = (u32, &'a [u8])>,
+    ) {
+        self.out.push(PARTIAL);
+        write_varint(&mut self.out, symbol_count as u64);
+        for (encoding_symbol_id, payload) in symbols {
+            write_varint(&mut self.out, encoding_symbol_id as u64);
+            self.out.extend_from_slice(payload);
+        }
+    }
+
+    pub fn complete_block(&mut self, data: &[u8]) {
+        self.out.push(COMPLETE);
+        self.out.extend_from_slice(data);
+    }
+
+    #[cfg(feature = "std")]
+    pub fn written_block(&mut self) {
+        self.out.push(WRITTEN);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.out
+    }
+}
+
+impl Checkpoint {
+    pub fn deserialize(data: &[u8]) -> Result<Checkpoint, RaptorQError> {
+        let mut reader = Reader::new(data, RaptorQError::InvalidCheckpoint);
+        reader.header(MAGIC, FORMAT_VERSION)?;
+        let config = ObjectTransmissionInformation::try_deserialize(reader.bytes(12)?)?;
+        let symbol_size = config.symbol_size() as usize;
+
+        let mut blocks = vec![];
+        for (source_block_number, (start, end)) in object_block_offsets(&config).iter().enumerate()
+        {
+            let block = match reader.byte()? {
+                PARTIAL => {
+                    // Each symbol is its payload, plus at least one byte of ESI
+                    let count = reader.count(symbol_size + 1)?;
+                    let mut packets = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        let encoding_symbol_id = reader.varint()?;
+                        if encoding_symbol_id >= 1 << 24 {
+                            return Err(RaptorQError::InvalidEncodingSymbolId(
+                                encoding_symbol_id.min(u32::MAX as u64) as u32,
+                            ));
+                        }
+                        packets.push(EncodingPacket::new(
+                            PayloadId::new(source_block_number as u8, encoding_symbol_id as u32),
+                            reader.bytes(symbol_size)?.to_vec(),
+                        ));
+                    }
+                    BlockCheckpoint::Partial(packets)
+                }
+                COMPLETE => {
+                    BlockCheckpoint::Complete(reader.bytes((end - start) as usize)?.to_vec())
+                }
+                WRITTEN => BlockCheckpoint::Written,
+                _ => return Err(reader.invalid("unknown block state")),
+            };
+            blocks.push(block);
+        }
+        reader.finish()?;
+
+        Ok(Checkpoint { config, blocks })
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::io::Cursor;
+    use std::vec::Vec;
+
+    use crate::{
+        DecodeAttemptPolicy, Decoder, DecoderConfig, Encoder, EncodingPacket,
+        ObjectTransmissionInformation, RaptorQError, StreamingDecoder,
+    };
+
+    fn encoded(data: &[u8], config: ObjectTransmissionInformation) -> Vec<EncodingPacket> {
+        let mut packets = Encoder::new(data, config).get_encoded_packets(5);
+        // Drop some source packets, so that the repair packets are needed
+        packets.retain(|packet| packet.payload_id().encoding_symbol_id() % 7 != 0);
+        packets.shuffle(&mut rand::rng());
+        packets
+    }
+
+    #[test]
+    fn resume_decoder() {
+        let data: Vec<u8> = (0..5_001).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 3, 1, 8);
+        let packets = encoded(&data, config);
+
+        // Checkpoint, and resume, after every packet, so that blocks are checkpointed in every
+        // state
+        let mut decoder = Decoder::new(config);
+        let mut result = None;
+        for packet in packets {
+            let checkpoint = decoder.checkpoint();
+            decoder = Decoder::resume(&checkpoint).unwrap();
+            assert_eq!(decoder.checkpoint(), checkpoint);
+            result = decoder.decode(packet);
+            if result.is_some() {
+                break;
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+    }
+
+    #[test]
+    fn resume_streaming_decoder() {
+        let data: Vec<u8> = (0..5_001).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 3, 1, 8);
+        // Block 0 is decoded, and written to the sink, before the checkpoint
+        let (first, second): (Vec<EncodingPacket>, Vec<EncodingPacket>) = encoded(&data, config)
+            .into_iter()
+            .partition(|packet| packet.payload_id().source_block_number() == 0);
+
+        let mut decoder = StreamingDecoder::new(Cursor::new(vec![]), config);
+        for packet in first {
+            decoder.decode(packet).unwrap();
+        }
+        for packet in second[..10].iter() {
+            decoder.decode(packet.clone()).unwrap();
+        }
+        assert!(decoder.block_status(0).unwrap().is_decoded());
+        let checkpoint = decoder.checkpoint();
+        let sink = decoder.into_inner();
+
+        // The written block is not stored, so can only be resumed with the same sink
+        assert_eq!(
+            Decoder::resume(&checkpoint).unwrap_err(),
+            RaptorQError::InvalidCheckpoint("decoded block data is not stored in the checkpoint")
+        );
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_attempt_policy(DecodeAttemptPolicy::Explicit);
+        let mut decoder =
+            StreamingDecoder::resume_with_decoder_config(sink, &checkpoint, &decoder_config)
+                .unwrap();
+        assert!(decoder.block_status(0).unwrap().is_decoded());
+        for packet in second[10..].iter() {
+            decoder.decode(packet.clone()).unwrap();
+        }
+        assert_eq!(decoder.try_finish().unwrap(), Ok(()));
+        assert_eq!(decoder.into_inner().into_inner(), data);
+    }
+
+    #[test]
+    fn invalid_checkpoint() {
+        let config = ObjectTransmissionInformation::new(1000, 64, 2, 1, 8);
+        let mut decoder = Decoder::new(config);
+        let packets = Encoder::new(&[7; 1000], config).get_encoded_packets(0);
+        decoder.decode(packets[0].clone());
+        let checkpoint = decoder.checkpoint();
+
+        for length in 0..checkpoint.len() {
+            assert!(Decoder::resume(&checkpoint[..length]).is_err());
+        }
+        let mut trailing = checkpoint.clone();
+        trailing.push(0);
+        assert_eq!(
+            Decoder::resume(&trailing).unwrap_err(),
+            RaptorQError::InvalidCheckpoint("trailing data")
+        );
+        let mut version = checkpoint.clone();
+        version[4] = 2;
+        assert_eq!(
+            Decoder::resume(&version).unwrap_err(),
+            RaptorQError::InvalidCheckpoint("unsupported version")
+        );
+    }
+}
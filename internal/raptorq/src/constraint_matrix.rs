@@ -61,8 +61,43 @@ pub fn enc_indices(
     indices
 }
 
+// Calls set(row, col) for each non-zero entry of the S LDPC rows of the constraint matrix
+// See section 5.3.3.3
 #[allow(non_snake_case)]
-fn generate_hdpc_rows(Kprime: usize, S: usize, H: usize) -> DenseOctetMatrix {
+pub fn ldpc_entries(source_block_symbols: u32, mut set: impl FnMut(usize, usize)) {
+    let S = num_ldpc_symbols(source_block_symbols) as usize;
+    let W = num_lt_symbols(source_block_symbols) as usize;
+    let B = W - S;
+    let P = num_pi_symbols(source_block_symbols) as usize;
+
+    // G_LDPC,1
+    for i in 0..B {
+        let a = 1 + i / S;
+
+        let b = i % S;
+        set(b, i);
+
+        let b = (b + a) % S;
+        set(b, i);
+
+        let b = (b + a) % S;
+        set(b, i);
+    }
+
+    // I_S
+    for i in 0..S {
+        set(i, i + B);
+    }
+
+    // G_LDPC,2
+    for i in 0..S {
+        set(i, (i % P) + W);
+        set(i, ((i + 1) % P) + W);
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn generate_hdpc_rows(Kprime: usize, S: usize, H: usize) -> DenseOctetMatrix {
     let mut matrix = DenseOctetMatrix::new(H, Kprime + S + H, 0);
     // Compute G_HDPC using recursive formulation, since this is much faster than a
     // naive matrix multiplication approach
@@ -122,40 +157,15 @@ pub fn generate_constraint_matrix<T: BinaryMatrix>(
     let Kprime = extended_source_block_symbols(source_block_symbols) as usize;
     let S = num_ldpc_symbols(source_block_symbols) as usize;
     let H = num_hdpc_symbols(source_block_symbols) as usize;
-    let W = num_lt_symbols(source_block_symbols) as usize;
-    let B = W - S;
     let P = num_pi_symbols(source_block_symbols) as usize;
     let L = num_intermediate_symbols(source_block_symbols) as usize;
 
     assert!(S + H + encoded_symbol_indices.len() >= L);
     let mut matrix = T::new(S + H + encoded_symbol_indices.len(), L, P);
 
-    // G_LDPC,1
-    // See section 5.3.3.3
-    for i in 0..B {
-        let a = 1 + i / S;
-
-        let b = i % S;
-        matrix.set(b, i, Octet::one());
-
-        let b = (b + a) % S;
-        matrix.set(b, i, Octet::one());
-
-        let b = (b + a) % S;
-        matrix.set(b, i, Octet::one());
-    }
-
-    // I_S
-    for i in 0..S {
-        matrix.set(i, i + B, Octet::one());
-    }
-
-    // G_LDPC,2
-    // See section 5.3.3.3
-    for i in 0..S {
-        matrix.set(i, (i % P) + W, Octet::one());
-        matrix.set(i, ((i + 1) % P) + W, Octet::one());
-    }
+    ldpc_entries(source_block_symbols, |row, col| {
+        matrix.set(row, col, Octet::one())
+    });
 
     // G_ENC
     let lt_symbols = num_lt_symbols(Kprime as u32);
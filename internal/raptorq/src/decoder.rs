@@ -1,8 +1,8 @@
 #[cfg(feature = "std")]
-use std::{collections::HashSet as Set, iter, vec::Vec};
+use std::{cmp::min, collections::HashSet as Set, fmt, iter, vec::Vec};
 
 #[cfg(not(feature = "std"))]
-use core::iter;
+use core::{cmp::min, fmt, iter};
 
 #[cfg(not(feature = "std"))]
 use alloc::{collections::BTreeSet as Set, vec::Vec};
@@ -11,11 +11,16 @@ use crate::base::EncodingPacket;
 use crate::base::ObjectTransmissionInformation;
 use crate::base::intermediate_tuple;
 use crate::base::partition;
+use crate::checkpoint::{BlockCheckpoint, Checkpoint, CheckpointWriter};
 use crate::constraint_matrix::enc_indices;
 use crate::constraint_matrix::generate_constraint_matrix;
-use crate::encoder::SPARSE_MATRIX_THRESHOLD;
-use crate::matrix::{BinaryMatrix, DenseBinaryMatrix};
-use crate::octet_matrix::DenseOctetMatrix;
+use crate::constraint_matrix::{generate_hdpc_rows, ldpc_entries};
+use crate::encoder::{MAX_ENCODING_SYMBOL_ID, SPARSE_MATRIX_THRESHOLD};
+use crate::error::RaptorQError;
+use crate::incremental_solver::IncrementalSymbolDecoder;
+use crate::matrix::DenseBinaryMatrix;
+use crate::octet::Octet;
+use crate::operation_vector::{SymbolOps, perform_ops};
 use crate::pi_solver::fused_inverse_mul_symbols;
 use crate::sparse_matrix::SparseBinaryMatrix;
 use crate::symbol::Symbol;
@@ -25,6 +30,8 @@ use crate::systematic_constants::{
     calculate_p1, extended_source_block_symbols, num_lt_symbols, num_pi_symbols, systematic_index,
 };
 use crate::util::int_div_ceil;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
@@ -34,28 +41,42 @@ pub struct Decoder {
     config: ObjectTransmissionInformation,
     block_decoders: Vec<SourceBlockDecoder>,
     blocks: Vec<Option<Vec<u8>>>,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    max_active_blocks: Option<u32>,
+    // Packets rejected before reaching a source block decoder
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    rejected_packets: RejectedPackets,
 }
 
 impl Decoder {
     pub fn new(config: ObjectTransmissionInformation) -> Decoder {
+        Decoder::with_decoder_config(config, &DecoderConfig::new())
+    }
+
+    pub fn with_decoder_config(
+        config: ObjectTransmissionInformation,
+        decoder_config: &DecoderConfig,
+    ) -> Decoder {
         let kt = int_div_ceil(config.transfer_length(), config.symbol_size() as u64);
 
         let (kl, ks, zl, zs) = partition(kt, config.source_blocks());
 
         let mut decoders = vec![];
         for i in 0..zl {
-            decoders.push(SourceBlockDecoder::new(
+            decoders.push(SourceBlockDecoder::with_decoder_config(
                 i as u8,
                 &config,
                 u64::from(kl) * u64::from(config.symbol_size()),
+                decoder_config,
             ));
         }
 
         for i in zl..(zl + zs) {
-            decoders.push(SourceBlockDecoder::new(
+            decoders.push(SourceBlockDecoder::with_decoder_config(
                 i as u8,
                 &config,
                 u64::from(ks) * u64::from(config.symbol_size()),
+                decoder_config,
             ));
         }
 
@@ -63,6 +84,8 @@ impl Decoder {
             config,
             block_decoders: decoders,
             blocks: vec![None; (zl + zs) as usize],
+            max_active_blocks: decoder_config.max_active_blocks,
+            rejected_packets: RejectedPackets::default(),
         }
     }
 
@@ -73,31 +96,372 @@ impl Decoder {
         }
     }
 
+    /// Enables or disables on-the-fly Gaussian elimination for every source block.
+    /// See [`SourceBlockDecoder::set_incremental_decoding`].
+    pub fn set_incremental_decoding(&mut self, enabled: bool) {
+        for block_decoder in self.block_decoders.iter_mut() {
+            block_decoder.set_incremental_decoding(enabled);
+        }
+    }
+
+    /// Adds a packet, and returns the object once it has been decoded. Invalid packets, and
+    /// packets over the memory limits of the [`DecoderConfig`], are dropped and counted in
+    /// [`DecodeStatus::rejected_packets`].
     pub fn decode(&mut self, packet: EncodingPacket) -> Option<Vec<u8>> {
-        let block_number = packet.payload_id.source_block_number() as usize;
-        if self.blocks[block_number].is_none() {
+        if let Some(block_number) = self.accept(&packet) {
             self.blocks[block_number] =
                 self.block_decoders[block_number].decode(iter::once(packet));
         }
-        for block in self.blocks.iter() {
-            if block.is_none() {
-                return None;
+        self.assemble_result()
+    }
+
+    /// Adds a batch of packets, then attempts to decode each source block which received any of
+    /// them. With the `parallel` feature, those blocks are solved concurrently on the rayon
+    /// thread pool.
+    pub fn decode_packets<T: IntoIterator<Item = EncodingPacket>>(
+        &mut self,
+        packets: T,
+    ) -> Option<Vec<u8>> {
+        let mut grouped: Vec<Vec<EncodingPacket>> = vec![vec![]; self.blocks.len()];
+        let mut active_blocks = self.active_blocks();
+        for packet in packets {
+            let block_number = match self.validate_source_block_number(&packet) {
+                Ok(block_number) => block_number,
+                Err(error) => {
+                    self.rejected_packets.record(&error);
+                    continue;
+                }
+            };
+            if self.blocks[block_number].is_some() {
+                continue;
             }
+            // Packets over the active block limit are dropped
+            if grouped[block_number].is_empty() && !self.is_active(block_number) {
+                if let Some(limit) = self.max_active_blocks
+                    && active_blocks >= limit
+                {
+                    self.rejected_packets
+                        .record(&RaptorQError::ActiveBlockLimit {
+                            source_block_number: block_number as u8,
+                            limit,
+                        });
+                    continue;
+                }
+                active_blocks += 1;
+            }
+            grouped[block_number].push(packet);
         }
+        self.map_blocks(grouped, |packets, block_decoder, block| {
+            if !packets.is_empty() {
+                *block = block_decoder.decode(packets);
+            }
+        });
+        self.assemble_result()
+    }
 
-        let mut result = vec![];
+    // Applies `f` to the input, decoder and decoded data of every source block, concurrently
+    // when the `parallel` feature is enabled
+    fn map_blocks<T: Send, R: Send>(
+        &mut self,
+        inputs: Vec<T>,
+        f: impl Fn(T, &mut SourceBlockDecoder, &mut Option<Vec<u8>>) -> R + Sync + Send,
+    ) -> Vec<R> {
+        #[cfg(feature = "parallel")]
+        return inputs
+            .into_par_iter()
+            .zip(self.block_decoders.par_iter_mut())
+            .zip(self.blocks.par_iter_mut())
+            .map(|((input, block_decoder), block)| f(input, block_decoder, block))
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        return inputs
+            .into_iter()
+            .zip(self.block_decoders.iter_mut())
+            .zip(self.blocks.iter_mut())
+            .map(|((input, block_decoder), block)| f(input, block_decoder, block))
+            .collect();
+    }
+
+    /// Like [`Decoder::decode`], but replays plans from `cache` when possible. See
+    /// [`SourceBlockDecoder::decode_with_plan_cache`].
+    pub fn decode_with_plan_cache(
+        &mut self,
+        packet: EncodingPacket,
+        cache: &mut DecodingPlanCache,
+    ) -> Option<Vec<u8>> {
+        if let Some(block_number) = self.accept(&packet) {
+            self.blocks[block_number] =
+                self.block_decoders[block_number].decode_with_plan_cache(iter::once(packet), cache);
+        }
+        self.assemble_result()
+    }
+
+    /// Like [`Decoder::decode`], but returns an error instead of dropping the packet if it does
+    /// not belong to this object, has an invalid ESI, or its payload is not exactly one symbol,
+    /// or if it exceeds the memory limits of the [`DecoderConfig`].
+    pub fn try_decode(&mut self, packet: EncodingPacket) -> Result<Option<Vec<u8>>, RaptorQError> {
+        if let Some(block_number) = self.try_accept(&packet)? {
+            self.blocks[block_number] =
+                self.block_decoders[block_number].try_decode(iter::once(packet))?;
+        }
+        Ok(self.assemble_result())
+    }
+
+    /// Adds a packet, and returns the source block number and data of its block, if this
+    /// packet completed the block. Padding beyond the transfer length is removed from the
+    /// last block.
+    pub fn decode_block(&mut self, packet: EncodingPacket) -> Option<(u8, Vec<u8>)> {
+        let source_block_number = packet.payload_id.source_block_number();
+        let block_number = self.accept(&packet)?;
+        self.blocks[block_number] = self.block_decoders[block_number].decode(iter::once(packet));
+        self.trimmed_block(block_number)
+            .map(|block| (source_block_number, block))
+    }
+
+    /// Like [`Decoder::decode_block`], but returns an error instead of dropping the packet if it
+    /// does not belong to this object, has an invalid ESI, or its payload is not exactly one
+    /// symbol, or if it exceeds the memory limits of the [`DecoderConfig`].
+    pub fn try_decode_block(
+        &mut self,
+        packet: EncodingPacket,
+    ) -> Result<Option<(u8, Vec<u8>)>, RaptorQError> {
+        let source_block_number = packet.payload_id.source_block_number();
+        let Some(block_number) = self.try_accept(&packet)? else {
+            return Ok(None);
+        };
+        self.blocks[block_number] =
+            self.block_decoders[block_number].try_decode(iter::once(packet))?;
+        Ok(self
+            .trimmed_block(block_number)
+            .map(|block| (source_block_number, block)))
+    }
+
+    // Returns the index of the packet's source block, if it should be given to the block's
+    // decoder, or an error if it was rejected. Packets for decoded blocks are ignored
+    fn try_accept(&mut self, packet: &EncodingPacket) -> Result<Option<usize>, RaptorQError> {
+        let result = self
+            .validate_source_block_number(packet)
+            .and_then(|block_number| {
+                if self.blocks[block_number].is_some() {
+                    return Ok(None);
+                }
+                self.check_active_limit(block_number)?;
+                Ok(Some(block_number))
+            });
+        if let Err(ref error) = result {
+            self.rejected_packets.record(error);
+        }
+        result
+    }
+
+    // Like try_accept(), but rejected packets are dropped
+    fn accept(&mut self, packet: &EncodingPacket) -> Option<usize> {
+        self.try_accept(packet).ok().flatten()
+    }
+
+    fn validate_source_block_number(&self, packet: &EncodingPacket) -> Result<usize, RaptorQError> {
+        let source_block_number = packet.payload_id.source_block_number();
+        if source_block_number as usize >= self.blocks.len() {
+            return Err(RaptorQError::InvalidSourceBlockNumber {
+                source_block_number,
+                source_blocks: self.config.source_blocks(),
+            });
+        }
+        Ok(source_block_number as usize)
+    }
+
+    // A block is active from its first packet until it is decoded
+    fn is_active(&self, block_number: usize) -> bool {
+        self.blocks[block_number].is_none()
+            && !self.block_decoders[block_number].received_esi.is_empty()
+    }
+
+    fn active_blocks(&self) -> u32 {
+        (0..self.blocks.len())
+            .filter(|&block_number| self.is_active(block_number))
+            .count() as u32
+    }
+
+    // Returns an error if accepting a packet for the block would exceed the limit on active blocks
+    fn check_active_limit(&self, block_number: usize) -> Result<(), RaptorQError> {
+        let Some(limit) = self.max_active_blocks else {
+            return Ok(());
+        };
+        if !self.is_active(block_number) && self.active_blocks() >= limit {
+            return Err(RaptorQError::ActiveBlockLimit {
+                source_block_number: block_number as u8,
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the approximate number of bytes retained by the decoder, including received
+    /// symbols, the state of incremental decoding, and decoded blocks.
+    ///
+    /// The matrices of a decoding attempt only exist during the attempt, and are not included.
+    /// Nor is a [`DecodingPlanCache`], which reports its own [`DecodingPlanCache::memory_usage`].
+    pub fn memory_usage(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        for block_decoder in self.block_decoders.iter() {
+            bytes += block_decoder.memory_usage();
+        }
+        bytes += size_of::<Option<Vec<u8>>>() * self.blocks.len();
         for block in self.blocks.iter().flatten() {
-            result.extend(block);
+            bytes += block.len();
+        }
+        bytes
+    }
+
+    /// Returns the reception progress of the entire object, summed over all source blocks.
+    pub fn status(&self) -> DecodeStatus {
+        DecodeStatus::merged(self.block_decoders.iter().map(SourceBlockDecoder::status))
+            .with_rejected_packets(&self.rejected_packets)
+    }
+
+    /// Returns the reception progress of a single source block, or `None` if the source block
+    /// number is not part of this object.
+    pub fn block_status(&self, source_block_number: u8) -> Option<DecodeStatus> {
+        self.block_decoders
+            .get(source_block_number as usize)
+            .map(SourceBlockDecoder::status)
+    }
+
+    // Returns a decoded block, without any padding beyond the end of the object
+    fn trimmed_block(&self, block_number: usize) -> Option<Vec<u8>> {
+        let block = self.blocks[block_number].as_ref()?;
+        let offset: u64 = self.block_decoders[..block_number]
+            .iter()
+            .map(|decoder| decoder.block_length())
+            .sum();
+        let length = min(
+            block.len() as u64,
+            self.config.transfer_length().saturating_sub(offset),
+        );
+        Some(block[..length as usize].to_vec())
+    }
+
+    /// Attempts to decode every incomplete source block, regardless of the attempt policy. With
+    /// the `parallel` feature, the blocks are solved concurrently.
+    ///
+    /// On failure, returns the reason the first incomplete block could not be decoded. The
+    /// reasons for other blocks are available from [`Decoder::block_status`].
+    pub fn try_finish(&mut self) -> Result<Vec<u8>, DecodeFailure> {
+        self.finish()
+            .map_err(|failures| failures.first().unwrap().reason())
+    }
+
+    /// Signals the end of input. Attempts to decode every incomplete source block, like
+    /// [`Decoder::try_finish`], and on failure returns why each block which could not be decoded
+    /// failed, in order of source block number.
+    pub fn finish(&mut self) -> Result<Vec<u8>, Vec<BlockFailure>> {
+        let failures = self.map_blocks(vec![(); self.blocks.len()], |_, block_decoder, block| {
+            if block.is_some() {
+                return None;
+            }
+            match block_decoder.finish() {
+                Ok(data) => {
+                    *block = Some(data);
+                    None
+                }
+                Err(failure) => Some(failure),
+            }
+        });
+        let failures: Vec<BlockFailure> = failures.into_iter().flatten().collect();
+        if failures.is_empty() {
+            Ok(self.assemble_result().unwrap())
+        } else {
+            Err(failures)
         }
+    }
+
+    /// Returns the data of a source block, or `None` if it has not been decoded or the source
+    /// block number is not part of this object. The last block is truncated to the end of the
+    /// object.
+    pub fn decoded_block(&self, source_block_number: u8) -> Option<Vec<u8>> {
+        if source_block_number as usize >= self.blocks.len() {
+            return None;
+        }
+        self.trimmed_block(source_block_number as usize)
+    }
 
+    /// Returns the data of the decoded source blocks, and the source symbols received so far for
+    /// the others, along with which source symbols are present. See
+    /// [`SourceBlockDecoder::partial_result`].
+    pub fn partial_result(&self) -> PartialResult {
+        let mut result = PartialResult::default();
+        for (block_decoder, block) in self.block_decoders.iter().zip(self.blocks.iter()) {
+            match block {
+                Some(data) => {
+                    for _ in 0..block_decoder.source_block_symbols {
+                        result.push_symbol(true);
+                    }
+                    result.data.extend_from_slice(data);
+                }
+                None => result.append(block_decoder.partial_result()),
+            }
+        }
         result.truncate(self.config.transfer_length() as usize);
-        Some(result)
+        result
+    }
+
+    /// Serializes the received symbols of each incomplete source block, and the data of each
+    /// decoded block, into a compact, versioned format. [`Decoder::resume`] reconstructs the
+    /// decoder from it, so that progress can be persisted and survive a restart.
+    ///
+    /// Statistics, such as the number of duplicate symbols, are not included.
+    pub fn checkpoint(&self) -> Vec<u8> {
+        let mut writer = CheckpointWriter::new(&self.config);
+        for (block_decoder, block) in self.block_decoders.iter().zip(self.blocks.iter()) {
+            match block {
+                Some(data) => writer.complete_block(data),
+                None => writer.partial_block(
+                    block_decoder.status().received_symbols(),
+                    block_decoder.received_symbols(),
+                ),
+            }
+        }
+        writer.finish()
+    }
+
+    /// Reconstructs a decoder from a checkpoint created by [`Decoder::checkpoint`].
+    pub fn resume(checkpoint: &[u8]) -> Result<Decoder, RaptorQError> {
+        Decoder::resume_with_decoder_config(checkpoint, &DecoderConfig::new())
+    }
+
+    /// Like [`Decoder::resume`], but configures the decoder with `decoder_config`, which is not
+    /// stored in the checkpoint.
+    ///
+    /// Checkpoints from a [`crate::StreamingDecoder`] which has written blocks to its sink are
+    /// rejected, since they do not contain the data of those blocks.
+    pub fn resume_with_decoder_config(
+        checkpoint: &[u8],
+        decoder_config: &DecoderConfig,
+    ) -> Result<Decoder, RaptorQError> {
+        let checkpoint = Checkpoint::deserialize(checkpoint)?;
+        let mut decoder = Decoder::with_decoder_config(checkpoint.config, decoder_config);
+        for (i, block) in checkpoint.blocks.into_iter().enumerate() {
+            match block {
+                BlockCheckpoint::Partial(packets) => {
+                    decoder.blocks[i] = decoder.block_decoders[i].restore(packets)?;
+                }
+                BlockCheckpoint::Complete(data) => {
+                    decoder.block_decoders[i].mark_decoded();
+                    decoder.blocks[i] = Some(data);
+                }
+                BlockCheckpoint::Written => {
+                    return Err(RaptorQError::InvalidCheckpoint(
+                        "decoded block data is not stored in the checkpoint",
+                    ));
+                }
+            }
+        }
+        Ok(decoder)
     }
 
     #[cfg(not(feature = "python"))]
     pub fn add_new_packet(&mut self, packet: EncodingPacket) {
-        let block_number = packet.payload_id.source_block_number() as usize;
-        if self.blocks[block_number].is_none() {
+        if let Some(block_number) = self.accept(&packet) {
             self.blocks[block_number] =
                 self.block_decoders[block_number].decode(iter::once(packet));
         }
@@ -105,6 +469,10 @@ impl Decoder {
 
     #[cfg(not(feature = "python"))]
     pub fn get_result(&self) -> Option<Vec<u8>> {
+        self.assemble_result()
+    }
+
+    fn assemble_result(&self) -> Option<Vec<u8>> {
         for block in self.blocks.iter() {
             if block.is_none() {
                 return None;
@@ -120,6 +488,485 @@ impl Decoder {
     }
 }
 
+/// Determines when a decoder attempts to solve a source block, once it may have received enough
+/// symbols to do so.
+///
+/// Each attempt with exactly K symbols fails with a small but non-trivial probability, and
+/// every symbol received beyond K makes failure much less likely.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum DecodeAttemptPolicy {
+    /// Attempt once K plus the given number of symbols have been received, and again after
+    /// every subsequent symbol
+    Overhead(u32),
+    /// Attempt once K plus the given number of symbols have been received. After the n-th
+    /// failed attempt, wait for 2^(n-1) more symbols before attempting again
+    ExponentialBackoff(u32),
+    /// Only attempt when `try_finish()` is called
+    Explicit,
+}
+
+/// Configuration for [`Decoder`] and [`SourceBlockDecoder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct DecoderConfig {
+    attempt_policy: DecodeAttemptPolicy,
+    incremental_decoding: bool,
+    max_retained_overhead: Option<u32>,
+    max_active_blocks: Option<u32>,
+    consistency_check: bool,
+}
+
+impl DecoderConfig {
+    pub fn new() -> DecoderConfig {
+        DecoderConfig {
+            attempt_policy: DecodeAttemptPolicy::Overhead(0),
+            incremental_decoding: false,
+            max_retained_overhead: None,
+            max_active_blocks: None,
+            consistency_check: false,
+        }
+    }
+
+    pub fn set_attempt_policy(&mut self, policy: DecodeAttemptPolicy) {
+        self.attempt_policy = policy;
+    }
+
+    /// See [`SourceBlockDecoder::set_incremental_decoding`]
+    pub fn set_incremental_decoding(&mut self, enabled: bool) {
+        self.incremental_decoding = enabled;
+    }
+
+    /// Limits the symbols retained by each source block to K plus `overhead`, and one more for
+    /// each failed decoding attempt. Further repair symbols are rejected, with
+    /// [`RaptorQError::RetainedSymbolLimit`]. Source symbols are always accepted.
+    ///
+    /// Unlimited by default.
+    pub fn set_max_retained_overhead(&mut self, overhead: Option<u32>) {
+        self.max_retained_overhead = overhead;
+    }
+
+    /// Limits the number of source blocks a [`Decoder`] decodes concurrently. A block is active
+    /// from its first accepted packet until it is decoded, and packets for other blocks are
+    /// rejected with [`RaptorQError::ActiveBlockLimit`] while the limit is reached.
+    ///
+    /// This bounds the symbols retained at once. The decoder of every source block is still
+    /// created up front, including its table of K source symbol slots, which the limit does not
+    /// bound.
+    ///
+    /// Unlimited by default.
+    pub fn set_max_active_blocks(&mut self, blocks: Option<u32>) {
+        self.max_active_blocks = blocks;
+    }
+
+    /// See [`SourceBlockDecoder::set_consistency_check`]
+    pub fn set_consistency_check(&mut self, enabled: bool) {
+        self.consistency_check = enabled;
+    }
+}
+
+impl Default for DecoderConfig {
+    fn default() -> DecoderConfig {
+        DecoderConfig::new()
+    }
+}
+
+/// Reason a decoding attempt did not recover a source block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum DecodeFailure {
+    /// Fewer than K distinct symbols have been received
+    InsufficientSymbols { received: u32, needed: u32 },
+    /// The received symbols do not determine the source block. At least one more is needed
+    SingularSystem,
+    /// The consistency check found that some received symbols were corrupted, and could not
+    /// isolate them. More symbols are needed
+    InconsistentSymbols,
+}
+
+/// Reason a source block could not be decoded at the end of input. Returned by
+/// [`Decoder::finish`] and [`SourceBlockDecoder::finish`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum BlockFailure {
+    /// Fewer than K distinct symbols were received
+    InsufficientSymbols {
+        source_block_number: u8,
+        received: u32,
+        needed: u32,
+    },
+    /// The received symbols do not determine the source block. At least one more is needed
+    SingularSystem { source_block_number: u8 },
+    /// The consistency check found that some received symbols were corrupted, and could not
+    /// isolate them
+    InconsistentSymbols { source_block_number: u8 },
+}
+
+impl BlockFailure {
+    fn new(source_block_number: u8, reason: DecodeFailure) -> BlockFailure {
+        match reason {
+            DecodeFailure::InsufficientSymbols { received, needed } => {
+                BlockFailure::InsufficientSymbols {
+                    source_block_number,
+                    received,
+                    needed,
+                }
+            }
+            DecodeFailure::SingularSystem => BlockFailure::SingularSystem {
+                source_block_number,
+            },
+            DecodeFailure::InconsistentSymbols => BlockFailure::InconsistentSymbols {
+                source_block_number,
+            },
+        }
+    }
+
+    pub fn source_block_number(&self) -> u8 {
+        match *self {
+            BlockFailure::InsufficientSymbols {
+                source_block_number,
+                ..
+            }
+            | BlockFailure::SingularSystem {
+                source_block_number,
+            }
+            | BlockFailure::InconsistentSymbols {
+                source_block_number,
+            } => source_block_number,
+        }
+    }
+
+    /// Returns the reason, without the source block number
+    pub fn reason(&self) -> DecodeFailure {
+        match *self {
+            BlockFailure::InsufficientSymbols {
+                received, needed, ..
+            } => DecodeFailure::InsufficientSymbols { received, needed },
+            BlockFailure::SingularSystem { .. } => DecodeFailure::SingularSystem,
+            BlockFailure::InconsistentSymbols { .. } => DecodeFailure::InconsistentSymbols,
+        }
+    }
+}
+
+impl fmt::Display for BlockFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BlockFailure::InsufficientSymbols {
+                source_block_number,
+                received,
+                needed,
+            } => write!(
+                f,
+                "source block {source_block_number} received {received} of {needed} symbols, {} more needed",
+                needed - received
+            ),
+            BlockFailure::SingularSystem {
+                source_block_number,
+            } => write!(
+                f,
+                "source block {source_block_number} could not be solved, at least 1 more symbol needed"
+            ),
+            BlockFailure::InconsistentSymbols {
+                source_block_number,
+            } => write!(
+                f,
+                "source block {source_block_number} received corrupted symbols, more symbols needed"
+            ),
+        }
+    }
+}
+
+/// Reception progress of a source block, or of an entire object.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct DecodeStatus {
+    source_symbols: u32,
+    received_source_symbols: u32,
+    received_repair_symbols: u32,
+    duplicate_symbols: u32,
+    symbols_needed: u32,
+    attempt_pending: bool,
+    failed_attempts: u32,
+    inconsistent_symbols: u32,
+    rejected_packets: RejectedPackets,
+    last_failure: Option<DecodeFailure>,
+    decoded: bool,
+}
+
+impl DecodeStatus {
+    // Sums the progress of the source blocks of an object
+    pub(crate) fn merged(blocks: impl Iterator<Item = DecodeStatus>) -> DecodeStatus {
+        let mut status = DecodeStatus {
+            decoded: true,
+            ..DecodeStatus::default()
+        };
+        for block in blocks {
+            status.merge(&block);
+        }
+        status
+    }
+
+    // Adds packets which were rejected before reaching a source block
+    pub(crate) fn with_rejected_packets(mut self, rejected_packets: &RejectedPackets) -> Self {
+        self.rejected_packets.add(rejected_packets);
+        self
+    }
+
+    fn merge(&mut self, other: &DecodeStatus) {
+        self.source_symbols += other.source_symbols;
+        self.received_source_symbols += other.received_source_symbols;
+        self.received_repair_symbols += other.received_repair_symbols;
+        self.duplicate_symbols += other.duplicate_symbols;
+        self.symbols_needed += other.symbols_needed;
+        self.attempt_pending |= other.attempt_pending;
+        self.failed_attempts += other.failed_attempts;
+        self.inconsistent_symbols += other.inconsistent_symbols;
+        self.rejected_packets.add(&other.rejected_packets);
+        self.last_failure = self.last_failure.or(other.last_failure);
+        self.decoded &= other.decoded;
+    }
+
+    /// Number of source symbols, K
+    pub fn source_symbols(&self) -> u32 {
+        self.source_symbols
+    }
+
+    /// Number of distinct symbols received
+    pub fn received_symbols(&self) -> u32 {
+        self.received_source_symbols + self.received_repair_symbols
+    }
+
+    /// Number of distinct source symbols received
+    pub fn received_source_symbols(&self) -> u32 {
+        self.received_source_symbols
+    }
+
+    /// Number of distinct repair symbols received
+    pub fn received_repair_symbols(&self) -> u32 {
+        self.received_repair_symbols
+    }
+
+    /// Number of symbols which were dropped, because their ESI had already been received
+    pub fn duplicate_symbols(&self) -> u32 {
+        self.duplicate_symbols
+    }
+
+    /// Minimum number of additional symbols required before decoding can succeed. Decoding
+    /// is not guaranteed to succeed once they are received.
+    ///
+    /// This is zero once decoded, while [`DecodeStatus::attempt_pending`] is set, and for a block
+    /// with no source symbols. A block whose decoding attempt failed with the symbols received
+    /// so far needs at least one more.
+    pub fn symbols_needed(&self) -> u32 {
+        self.symbols_needed
+    }
+
+    /// Whether enough symbols have been received to attempt decoding, but the
+    /// [`DecodeAttemptPolicy`] has not triggered an attempt with them yet. For an object, whether
+    /// any source block is in this state.
+    pub fn attempt_pending(&self) -> bool {
+        self.attempt_pending
+    }
+
+    /// Number of decoding attempts which failed, because the system was singular or the received
+    /// symbols were inconsistent
+    pub fn failed_attempts(&self) -> u32 {
+        self.failed_attempts
+    }
+
+    /// Number of received symbols which the consistency check found to be corrupted, and
+    /// discarded
+    pub fn inconsistent_symbols(&self) -> u32 {
+        self.inconsistent_symbols
+    }
+
+    /// Number of packets which were rejected, by reason. For an object, this includes packets
+    /// which did not belong to any of its source blocks.
+    pub fn rejected_packets(&self) -> RejectedPackets {
+        self.rejected_packets
+    }
+
+    /// Reason the most recent decoding attempt failed, if any. For an object, this is the
+    /// failure of the first source block that has one.
+    pub fn last_failure(&self) -> Option<DecodeFailure> {
+        self.last_failure
+    }
+
+    pub fn is_decoded(&self) -> bool {
+        self.decoded
+    }
+}
+
+/// Number of packets rejected by a decoder, by reason. Part of [`DecodeStatus`].
+///
+/// Packets are rejected by the `try_*` methods with the corresponding [`RaptorQError`], and
+/// dropped by the others.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct RejectedPackets {
+    invalid_source_block_number: u32,
+    invalid_encoding_symbol_id: u32,
+    symbol_size_mismatch: u32,
+    retained_symbol_limit: u32,
+    active_block_limit: u32,
+}
+
+impl RejectedPackets {
+    // Counts a packet rejected with `error`
+    pub(crate) fn record(&mut self, error: &RaptorQError) {
+        let counter = match error {
+            RaptorQError::InvalidSourceBlockNumber { .. }
+            | RaptorQError::SourceBlockMismatch { .. } => &mut self.invalid_source_block_number,
+            RaptorQError::InvalidEncodingSymbolId(_) => &mut self.invalid_encoding_symbol_id,
+            RaptorQError::SymbolSizeMismatch { .. } => &mut self.symbol_size_mismatch,
+            RaptorQError::RetainedSymbolLimit { .. } => &mut self.retained_symbol_limit,
+            RaptorQError::ActiveBlockLimit { .. } => &mut self.active_block_limit,
+            _ => return,
+        };
+        *counter += 1;
+    }
+
+    fn add(&mut self, other: &RejectedPackets) {
+        self.invalid_source_block_number += other.invalid_source_block_number;
+        self.invalid_encoding_symbol_id += other.invalid_encoding_symbol_id;
+        self.symbol_size_mismatch += other.symbol_size_mismatch;
+        self.retained_symbol_limit += other.retained_symbol_limit;
+        self.active_block_limit += other.active_block_limit;
+    }
+
+    /// Total number of packets rejected for any reason
+    pub fn total(&self) -> u32 {
+        self.invalid_source_block_number
+            + self.invalid_encoding_symbol_id
+            + self.symbol_size_mismatch
+            + self.retained_symbol_limit
+            + self.active_block_limit
+    }
+
+    /// Packets whose source block number is not part of the object, or which were given to the
+    /// decoder of a different source block
+    pub fn invalid_source_block_number(&self) -> u32 {
+        self.invalid_source_block_number
+    }
+
+    /// Packets whose Encoding Symbol ID does not fit in 24 bits
+    pub fn invalid_encoding_symbol_id(&self) -> u32 {
+        self.invalid_encoding_symbol_id
+    }
+
+    /// Packets whose payload is not exactly one symbol
+    pub fn symbol_size_mismatch(&self) -> u32 {
+        self.symbol_size_mismatch
+    }
+
+    /// Packets over the retained symbol limit of the [`DecoderConfig`]
+    pub fn retained_symbol_limit(&self) -> u32 {
+        self.retained_symbol_limit
+    }
+
+    /// Packets over the active block limit of the [`DecoderConfig`]
+    pub fn active_block_limit(&self) -> u32 {
+        self.active_block_limit
+    }
+}
+
+/// Source data held by a decoder before decoding has completed, as returned by
+/// [`SourceBlockDecoder::partial_result`] and [`Decoder::partial_result`].
+///
+/// Missing source symbols are zero filled in the data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialResult {
+    data: Vec<u8>,
+    // Bit i is set if source symbol i is present, least significant bit first
+    bitmap: Vec<u8>,
+    symbol_count: u32,
+    present_symbols: u32,
+    // Sorted, non-adjacent [start, end) byte ranges of missing data
+    holes: Vec<(usize, usize)>,
+}
+
+impl PartialResult {
+    fn push_symbol(&mut self, present: bool) {
+        if self.symbol_count.is_multiple_of(8) {
+            self.bitmap.push(0);
+        }
+        if present {
+            *self.bitmap.last_mut().unwrap() |= 1 << (self.symbol_count % 8);
+            self.present_symbols += 1;
+        }
+        self.symbol_count += 1;
+    }
+
+    // Ranges must be added in order
+    fn push_hole(&mut self, start: usize, end: usize) {
+        match self.holes.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => self.holes.push((start, end)),
+        }
+    }
+
+    // Appends the result of the next source block of an object
+    fn append(&mut self, block: PartialResult) {
+        for i in 0..block.symbol_count {
+            self.push_symbol(block.is_present(i));
+        }
+        let offset = self.data.len();
+        for (start, end) in block.holes {
+            self.push_hole(offset + start, offset + end);
+        }
+        self.data.extend(block.data);
+    }
+
+    // Removes the padding beyond the end of the object
+    fn truncate(&mut self, length: usize) {
+        self.data.truncate(length);
+        self.holes.retain(|(start, _)| *start < length);
+        if let Some(last) = self.holes.last_mut() {
+            last.1 = min(last.1, length);
+        }
+    }
+
+    /// Returns the data, with missing symbols zero filled
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns a bitmap of the source symbols which are present, least significant bit first.
+    /// For an object, the symbols of each source block follow those of the previous block.
+    pub fn bitmap(&self) -> &[u8] {
+        &self.bitmap
+    }
+
+    /// Number of source symbols covered by the bitmap
+    pub fn symbol_count(&self) -> u32 {
+        self.symbol_count
+    }
+
+    /// Number of source symbols which are present
+    pub fn present_symbols(&self) -> u32 {
+        self.present_symbols
+    }
+
+    pub fn is_present(&self, symbol_index: u32) -> bool {
+        symbol_index < self.symbol_count
+            && self.bitmap[symbol_index as usize / 8] & (1 << (symbol_index % 8)) != 0
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.present_symbols == self.symbol_count
+    }
+
+    /// Returns the `[start, end)` byte ranges of the data which are missing, in order. When the
+    /// object uses sub-blocking, each missing symbol leaves a hole in every sub-block.
+    pub fn holes(&self) -> &[(usize, usize)] {
+        &self.holes
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct SourceBlockDecoder {
@@ -132,8 +979,41 @@ pub struct SourceBlockDecoder {
     repair_packets: Vec<EncodingPacket>,
     received_source_symbols: u32,
     received_esi: Set<u32>,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    duplicate_symbols: u32,
     decoded: bool,
     sparse_threshold: u32,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    incremental_decoding: bool,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    incremental: Option<IncrementalSymbolDecoder>,
+    #[cfg_attr(feature = "serde_support", serde(default = "default_attempt_policy"))]
+    attempt_policy: DecodeAttemptPolicy,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    max_retained_overhead: Option<u32>,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    consistency_check: bool,
+    // ESIs of the received symbols discarded by the consistency check
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    inconsistent_symbols: Vec<u32>,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    rejected_packets: RejectedPackets,
+    // Number of received symbols required before the next automatic decoding attempt
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    next_attempt: u32,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    failed_attempts: u32,
+    // Number of received symbols when the most recent decoding attempt failed
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    failed_attempt_symbols: u32,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    last_failure: Option<DecodeFailure>,
+}
+
+// Attempt policy of decoders serialized before it was configurable
+#[cfg(feature = "serde_support")]
+fn default_attempt_policy() -> DecodeAttemptPolicy {
+    DecoderConfig::new().attempt_policy
 }
 
 impl SourceBlockDecoder {
@@ -141,22 +1021,51 @@ impl SourceBlockDecoder {
         source_block_id: u8,
         config: &ObjectTransmissionInformation,
         block_length: u64,
+    ) -> SourceBlockDecoder {
+        SourceBlockDecoder::with_decoder_config(
+            source_block_id,
+            config,
+            block_length,
+            &DecoderConfig::new(),
+        )
+    }
+
+    pub fn with_decoder_config(
+        source_block_id: u8,
+        config: &ObjectTransmissionInformation,
+        block_length: u64,
+        decoder_config: &DecoderConfig,
     ) -> SourceBlockDecoder {
         let source_symbols = int_div_ceil(block_length, config.symbol_size() as u64);
 
-        SourceBlockDecoder {
+        let mut decoder = SourceBlockDecoder {
             source_block_id,
             symbol_size: config.symbol_size(),
             num_sub_blocks: config.sub_blocks(),
             symbol_alignment: config.symbol_alignment(),
             source_block_symbols: source_symbols,
-            source_symbols: vec![None; source_symbols as usize],
+            // Allocated when the first packet is received
+            source_symbols: vec![],
             repair_packets: vec![],
             received_source_symbols: 0,
             received_esi: Set::new(),
+            duplicate_symbols: 0,
             decoded: false,
             sparse_threshold: SPARSE_MATRIX_THRESHOLD,
-        }
+            incremental_decoding: false,
+            incremental: None,
+            attempt_policy: decoder_config.attempt_policy,
+            max_retained_overhead: decoder_config.max_retained_overhead,
+            consistency_check: decoder_config.consistency_check,
+            inconsistent_symbols: vec![],
+            rejected_packets: RejectedPackets::default(),
+            next_attempt: 0,
+            failed_attempts: 0,
+            failed_attempt_symbols: 0,
+            last_failure: None,
+        };
+        decoder.set_incremental_decoding(decoder_config.incremental_decoding);
+        decoder
     }
 
     #[cfg(any(test, feature = "benchmarking"))]
@@ -164,44 +1073,204 @@ impl SourceBlockDecoder {
         self.sparse_threshold = value;
     }
 
-    fn unpack_sub_blocks(&self, result: &mut [u8], symbol: &Symbol, symbol_index: usize) {
+    /// Enables or disables on-the-fly Gaussian elimination.
+    ///
+    /// By default, every decoding attempt after K symbols have been received builds the
+    /// constraint matrix and solves it from scratch. When enabled, received symbols are instead
+    /// eliminated as they arrive and the partially reduced system is kept between calls to
+    /// `decode()`, so each symbol only costs the work it contributes. This uses more memory, and
+    /// more symbol operations in total than a single successful attempt of the default solver.
+    pub fn set_incremental_decoding(&mut self, enabled: bool) {
+        self.incremental_decoding = enabled;
+        if !enabled {
+            self.incremental = None;
+            return;
+        }
+        // Before the first packet, the solver is created along with the other per-symbol state
+        if self.incremental.is_some() || self.decoded || self.source_symbols.is_empty() {
+            return;
+        }
+
+        let mut incremental =
+            IncrementalSymbolDecoder::new(self.source_block_symbols, self.symbol_size);
+        for (i, source) in self.source_symbols.iter().enumerate() {
+            if let Some(symbol) = source {
+                incremental.add_symbol(i as u32, symbol.clone());
+            }
+        }
+        for repair_packet in self.repair_packets.iter() {
+            let isi = Self::internal_symbol_id(
+                repair_packet.payload_id.encoding_symbol_id(),
+                self.source_block_symbols,
+            );
+            incremental.add_symbol(isi, Symbol::new(repair_packet.data.clone()));
+        }
+        self.incremental = Some(incremental);
+    }
+
+    /// Enables or disables verification of the solved intermediate symbols against the surplus
+    /// symbols, when more than K have been received.
+    ///
+    /// RaptorQ assumes that symbols are either received intact or not at all, so a corrupted
+    /// symbol silently corrupts the decoded block. When enabled, every received symbol is
+    /// regenerated from the solution and compared with what was received, and the solution is
+    /// checked against the padding, LDPC and HDPC constraints. On a mismatch, the inconsistent
+    /// symbols are isolated and the block is solved again without them. A single corrupted symbol
+    /// can be isolated if at least two surplus symbols were received, as can any number of
+    /// corrupted symbols which were not needed to solve. If isolation fails, decoding fails with
+    /// [`DecodeFailure::InconsistentSymbols`].
+    ///
+    /// Isolation solves the block repeatedly, so is expensive for large blocks.
+    pub fn set_consistency_check(&mut self, enabled: bool) {
+        self.consistency_check = enabled;
+    }
+
+    /// Returns the ESIs of the received symbols which the consistency check discarded.
+    pub fn inconsistent_symbols(&self) -> &[u32] {
+        &self.inconsistent_symbols
+    }
+
+    /// Returns the reception progress of this block.
+    pub fn status(&self) -> DecodeStatus {
+        let received = self.received_esi.len() as u32;
+        // Enough symbols were received, but the attempt policy has not triggered an attempt with
+        // them yet
+        let attempt_pending = !self.decoded
+            && received >= self.source_block_symbols
+            && !(self.failed_attempts > 0 && self.failed_attempt_symbols == received);
+        let symbols_needed = if self.decoded || attempt_pending || self.source_block_symbols == 0 {
+            0
+        } else if received < self.source_block_symbols {
+            self.source_block_symbols - received
+        } else {
+            // Decoding failed with the symbols received so far
+            1
+        };
+        DecodeStatus {
+            source_symbols: self.source_block_symbols,
+            received_source_symbols: self.received_source_symbols,
+            received_repair_symbols: self.repair_packets.len() as u32,
+            duplicate_symbols: self.duplicate_symbols,
+            symbols_needed,
+            attempt_pending,
+            failed_attempts: self.failed_attempts,
+            inconsistent_symbols: self.inconsistent_symbols.len() as u32,
+            rejected_packets: self.rejected_packets,
+            last_failure: self.last_failure,
+            decoded: self.decoded,
+        }
+    }
+
+    /// Returns the approximate number of bytes retained by the decoder, including received symbols
+    /// and the state of incremental decoding. See [`Decoder::memory_usage`].
+    pub fn memory_usage(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        bytes += size_of::<Option<Symbol>>() * self.source_symbols.len();
+        for symbol in self.source_symbols.iter().flatten() {
+            bytes += symbol.as_bytes().len();
+        }
+        bytes += size_of::<EncodingPacket>() * self.repair_packets.len();
+        for packet in self.repair_packets.iter() {
+            bytes += packet.data.len();
+        }
+        bytes += size_of::<u32>() * self.received_esi.len();
+        if let Some(ref incremental) = self.incremental {
+            bytes += incremental.size_in_bytes();
+        }
+        bytes
+    }
+
+    // Returns the ESI and payload of every received symbol
+    pub(crate) fn received_symbols(&self) -> impl Iterator<Item = (u32, &[u8])> {
+        let source_symbols = self
+            .source_symbols
+            .iter()
+            .enumerate()
+            .filter_map(|(i, symbol)| Some((i as u32, symbol.as_ref()?.as_bytes())));
+        let repair_symbols = self.repair_packets.iter().map(|packet| {
+            (
+                packet.payload_id.encoding_symbol_id(),
+                packet.data.as_slice(),
+            )
+        });
+        source_symbols.chain(repair_symbols)
+    }
+
+    // Marks the block as decoded, when its data was recovered from elsewhere, such as a checkpoint
+    pub(crate) fn mark_decoded(&mut self) {
+        self.decoded = true;
+        self.incremental = None;
+    }
+
+    // Length of the decoded block in bytes, including any padding
+    fn block_length(&self) -> u64 {
+        self.source_block_symbols as u64 * self.symbol_size as u64
+    }
+
+    // Returns the [start, end) ranges of the block which hold the sub-symbols of a source symbol
+    fn symbol_ranges(&self, symbol_index: usize) -> impl Iterator<Item = (usize, usize)> + use<> {
         let (tl, ts, nl, ns) = partition(
             (self.symbol_size / self.symbol_alignment as u16) as u32,
             self.num_sub_blocks,
         );
+        let alignment = self.symbol_alignment as usize;
+        let source_block_symbols = self.source_block_symbols as usize;
 
-        let mut symbol_offset = 0;
         let mut sub_block_offset = 0;
-        for sub_block in 0..(nl + ns) {
+        (0..(nl + ns)).map(move |sub_block| {
             let bytes = if sub_block < nl {
-                tl as usize * self.symbol_alignment as usize
+                tl as usize * alignment
             } else {
-                ts as usize * self.symbol_alignment as usize
+                ts as usize * alignment
             };
             let start = sub_block_offset + bytes * symbol_index;
-            result[start..start + bytes]
+            sub_block_offset += bytes * source_block_symbols;
+            (start, start + bytes)
+        })
+    }
+
+    fn unpack_sub_blocks(&self, result: &mut [u8], symbol: &Symbol, symbol_index: usize) {
+        let mut symbol_offset = 0;
+        for (start, end) in self.symbol_ranges(symbol_index) {
+            let bytes = end - start;
+            result[start..end]
                 .copy_from_slice(&symbol.as_bytes()[symbol_offset..symbol_offset + bytes]);
             symbol_offset += bytes;
-            sub_block_offset += bytes * self.source_block_symbols as usize;
         }
     }
 
-    fn try_pi_decode(
-        &mut self,
-        constraint_matrix: impl BinaryMatrix,
-        hdpc_rows: DenseOctetMatrix,
-        symbols: Vec<Symbol>,
-    ) -> Option<Vec<u8>> {
-        let intermediate_symbols = match fused_inverse_mul_symbols(
-            constraint_matrix,
-            hdpc_rows,
-            symbols,
-            self.source_block_symbols,
-        ) {
-            (None, _) => return None,
-            (Some(s), _) => s,
+    /// Returns the source symbols received so far, with sub-blocking undone, and which of them
+    /// are present. Useful when the block may never become decodable, such as for live media.
+    ///
+    /// Symbols recovered by decoding are not included, since the decoded block is not retained
+    /// after [`SourceBlockDecoder::decode`] returns it.
+    pub fn partial_result(&self) -> PartialResult {
+        let mut result = PartialResult {
+            data: vec![0; self.block_length() as usize],
+            ..PartialResult::default()
         };
+        let mut holes = vec![];
+        for i in 0..self.source_block_symbols as usize {
+            match self.source_symbols.get(i) {
+                Some(Some(symbol)) => {
+                    self.unpack_sub_blocks(&mut result.data, symbol, i);
+                    result.push_symbol(true);
+                }
+                _ => {
+                    holes.extend(self.symbol_ranges(i));
+                    result.push_symbol(false);
+                }
+            }
+        }
+        holes.sort_unstable();
+        for (start, end) in holes {
+            result.push_hole(start, end);
+        }
+        result
+    }
 
+    // Combines the received source symbols with those recovered from the intermediate symbols
+    fn rebuild_source_block(&mut self, intermediate_symbols: &[Symbol]) -> Vec<u8> {
         let mut result = vec![0; self.symbol_size as usize * self.source_block_symbols as usize];
         let lt_symbols = num_lt_symbols(self.source_block_symbols);
         let pi_symbols = num_pi_symbols(self.source_block_symbols);
@@ -212,7 +1281,7 @@ impl SourceBlockDecoder {
                 self.unpack_sub_blocks(&mut result, symbol, i);
             } else {
                 let rebuilt = self.rebuild_source_symbol(
-                    &intermediate_symbols,
+                    intermediate_symbols,
                     i as u32,
                     lt_symbols,
                     pi_symbols,
@@ -224,44 +1293,251 @@ impl SourceBlockDecoder {
         }
 
         self.decoded = true;
-        return Some(result);
+        result
     }
 
+    /// Adds packets, and returns the source block once it has been decoded. Invalid packets,
+    /// and packets over the retained symbol limit, are dropped and counted in
+    /// [`DecodeStatus::rejected_packets`].
     pub fn decode<T: IntoIterator<Item = EncodingPacket>>(
         &mut self,
         packets: T,
     ) -> Option<Vec<u8>> {
         for packet in packets {
-            assert_eq!(
-                self.source_block_id,
-                packet.payload_id.source_block_number()
-            );
+            // Invalid packets, and packets over the retained symbol limit, are dropped
+            if self.try_accept(&packet).is_ok() {
+                self.add_packet(packet);
+            }
+        }
+        self.attempt_decode()
+    }
 
-            let (payload_id, payload) = packet.split();
-            if self.received_esi.insert(payload_id.encoding_symbol_id()) {
-                if payload_id.encoding_symbol_id() >= self.source_block_symbols {
-                    // Repair symbol
-                    self.repair_packets
-                        .push(EncodingPacket::new(payload_id, payload));
-                } else {
-                    // Source symbol
-                    self.source_symbols[payload_id.encoding_symbol_id() as usize] =
-                        Some(Symbol::new(payload));
-                    self.received_source_symbols += 1;
-                }
+    /// Like [`SourceBlockDecoder::decode`], but looks up a plan for the received ESIs in `cache`
+    /// and replays it instead of solving from scratch. If there is none, the plan recorded while
+    /// solving is added to the cache.
+    ///
+    /// Incremental decoding, if enabled, is only used when the cache has no plan.
+    pub fn decode_with_plan_cache<T: IntoIterator<Item = EncodingPacket>>(
+        &mut self,
+        packets: T,
+        cache: &mut DecodingPlanCache,
+    ) -> Option<Vec<u8>> {
+        for packet in packets {
+            if self.try_accept(&packet).is_ok() {
+                self.add_packet(packet);
             }
         }
+        self.attempt_decode_with(Some(cache))
+    }
 
-        let num_extended_symbols = extended_source_block_symbols(self.source_block_symbols);
-        let num_padding_symbols = num_extended_symbols - self.source_block_symbols;
+    /// Like [`SourceBlockDecoder::decode`], but returns an error instead of dropping a packet
+    /// which belongs to a different source block, has an invalid ESI, or whose payload is not
+    /// exactly one symbol, or which exceeds the retained symbol limit.
+    ///
+    /// Packets preceding the rejected one are retained.
+    pub fn try_decode<T: IntoIterator<Item = EncodingPacket>>(
+        &mut self,
+        packets: T,
+    ) -> Result<Option<Vec<u8>>, RaptorQError> {
+        for packet in packets {
+            self.try_accept(&packet)?;
+            self.add_packet(packet);
+        }
+        Ok(self.attempt_decode())
+    }
 
+    // Returns an error if the packet is invalid, or over the retained symbol limit, and counts it
+    fn try_accept(&mut self, packet: &EncodingPacket) -> Result<(), RaptorQError> {
+        let result = self
+            .validate_packet(packet)
+            .and_then(|()| self.check_retained_limit(packet));
+        if let Err(ref error) = result {
+            self.rejected_packets.record(error);
+        }
+        result
+    }
+
+    // Like try_decode(), but without the retained symbol limit, which may be lower than when the
+    // packets were accepted, since failed attempts are not checkpointed
+    pub(crate) fn restore(
+        &mut self,
+        packets: Vec<EncodingPacket>,
+    ) -> Result<Option<Vec<u8>>, RaptorQError> {
+        for packet in packets {
+            self.validate_packet(&packet)?;
+            self.add_packet(packet);
+        }
+        Ok(self.attempt_decode())
+    }
+
+    fn validate_packet(&self, packet: &EncodingPacket) -> Result<(), RaptorQError> {
+        if packet.payload_id.source_block_number() != self.source_block_id {
+            return Err(RaptorQError::SourceBlockMismatch {
+                expected: self.source_block_id,
+                actual: packet.payload_id.source_block_number(),
+            });
+        }
+        // Only possible for payload IDs which were not constructed by PayloadId::try_new()
+        let encoding_symbol_id = packet.payload_id.encoding_symbol_id();
+        if encoding_symbol_id > MAX_ENCODING_SYMBOL_ID {
+            return Err(RaptorQError::InvalidEncodingSymbolId(encoding_symbol_id));
+        }
+        if packet.data.len() != self.symbol_size as usize {
+            return Err(RaptorQError::SymbolSizeMismatch {
+                expected: self.symbol_size as usize,
+                actual: packet.data.len(),
+            });
+        }
+        Ok(())
+    }
+
+    // Returns an error if retaining the packet would exceed the limit on retained symbols
+    fn check_retained_limit(&self, packet: &EncodingPacket) -> Result<(), RaptorQError> {
+        let Some(overhead) = self.max_retained_overhead else {
+            return Ok(());
+        };
+        let encoding_symbol_id = packet.payload_id.encoding_symbol_id();
+        if encoding_symbol_id < self.source_block_symbols
+            || self.received_esi.contains(&encoding_symbol_id)
+        {
+            return Ok(());
+        }
+        let limit = self
+            .source_block_symbols
+            .saturating_add(overhead)
+            .saturating_add(self.failed_attempts);
+        if self.received_esi.len() as u32 >= limit {
+            return Err(RaptorQError::RetainedSymbolLimit {
+                source_block_number: self.source_block_id,
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    // Allocates the per-symbol state on the first packet, so that blocks which have not received
+    // any cost little memory
+    fn allocate(&mut self) {
+        if !self.source_symbols.is_empty() {
+            return;
+        }
+        self.source_symbols = vec![None; self.source_block_symbols as usize];
+        if self.incremental_decoding && !self.decoded {
+            self.incremental = Some(IncrementalSymbolDecoder::new(
+                self.source_block_symbols,
+                self.symbol_size,
+            ));
+        }
+    }
+
+    fn add_packet(&mut self, packet: EncodingPacket) {
+        self.allocate();
+        let (payload_id, payload) = packet.split();
+        if self.received_esi.insert(payload_id.encoding_symbol_id()) {
+            if let Some(ref mut incremental) = self.incremental {
+                let isi = Self::internal_symbol_id(
+                    payload_id.encoding_symbol_id(),
+                    self.source_block_symbols,
+                );
+                incremental.add_symbol(isi, Symbol::new(payload.clone()));
+            }
+            if payload_id.encoding_symbol_id() >= self.source_block_symbols {
+                // Repair symbol
+                self.repair_packets
+                    .push(EncodingPacket::new(payload_id, payload));
+            } else {
+                // Source symbol
+                self.source_symbols[payload_id.encoding_symbol_id() as usize] =
+                    Some(Symbol::new(payload));
+                self.received_source_symbols += 1;
+            }
+        } else {
+            self.duplicate_symbols += 1;
+        }
+    }
+
+    // Attempts decoding, if permitted by the attempt policy
+    fn attempt_decode(&mut self) -> Option<Vec<u8>> {
+        self.attempt_decode_with(None)
+    }
+
+    fn attempt_decode_with(&mut self, cache: Option<&mut DecodingPlanCache>) -> Option<Vec<u8>> {
+        // Having every source symbol never requires solving, so is returned regardless of policy
+        if self.received_source_symbols != self.source_block_symbols {
+            let overhead = match self.attempt_policy {
+                DecodeAttemptPolicy::Overhead(overhead) => overhead,
+                DecodeAttemptPolicy::ExponentialBackoff(overhead) => overhead,
+                DecodeAttemptPolicy::Explicit => return None,
+            };
+            let received = self.received_esi.len() as u32;
+            if received < self.source_block_symbols.saturating_add(overhead)
+                || received < self.next_attempt
+            {
+                return None;
+            }
+        }
+        self.finish_with(cache).ok()
+    }
+
+    /// Attempts to decode the source block with the symbols received so far, regardless of the
+    /// attempt policy, and returns the reason if that was not possible.
+    pub fn try_finish(&mut self) -> Result<Vec<u8>, DecodeFailure> {
+        self.finish_with(None)
+    }
+
+    /// Signals the end of input. Like [`SourceBlockDecoder::try_finish`], but the failure
+    /// includes the source block number.
+    pub fn finish(&mut self) -> Result<Vec<u8>, BlockFailure> {
+        self.try_finish()
+            .map_err(|reason| BlockFailure::new(self.source_block_id, reason))
+    }
+
+    fn finish_with(
+        &mut self,
+        cache: Option<&mut DecodingPlanCache>,
+    ) -> Result<Vec<u8>, DecodeFailure> {
+        let result = self.solve(cache);
+        if let Err(failure) = result {
+            self.record_failure(failure);
+        }
+        result
+    }
+
+    fn record_failure(&mut self, failure: DecodeFailure) {
+        self.last_failure = Some(failure);
+        if matches!(
+            failure,
+            DecodeFailure::SingularSystem | DecodeFailure::InconsistentSymbols
+        ) {
+            self.failed_attempts += 1;
+            self.failed_attempt_symbols = self.received_esi.len() as u32;
+            let wait = match self.attempt_policy {
+                DecodeAttemptPolicy::ExponentialBackoff(_) => 1u32
+                    .checked_shl(self.failed_attempts - 1)
+                    .unwrap_or(u32::MAX),
+                _ => 1,
+            };
+            self.next_attempt = (self.received_esi.len() as u32).saturating_add(wait);
+        }
+    }
+
+    fn solve(
+        &mut self,
+        mut cache: Option<&mut DecodingPlanCache>,
+    ) -> Result<Vec<u8>, DecodeFailure> {
         // Case 1: the number of received packets is insufficient for decoding
         if self.received_esi.len() < self.source_block_symbols as usize {
-            return None;
+            return Err(DecodeFailure::InsufficientSymbols {
+                received: self.received_esi.len() as u32,
+                needed: self.source_block_symbols,
+            });
         }
 
-        // Case 2: we have all source symbols and can return them without decoding
-        if self.received_source_symbols == self.source_block_symbols {
+        // Case 2: we have all source symbols and can return them without decoding, unless they
+        // should be checked against the repair symbols
+        if self.received_source_symbols == self.source_block_symbols
+            && (!self.consistency_check || self.repair_packets.is_empty())
+        {
             let mut result =
                 vec![0; self.symbol_size as usize * self.source_block_symbols as usize];
             for (i, symbol) in self.source_symbols.iter().enumerate() {
@@ -269,48 +1545,268 @@ impl SourceBlockDecoder {
             }
 
             self.decoded = true;
-            return Some(result);
+            return Ok(result);
         }
 
         // Case 3: we may have sufficient symbols to do a standard decoding
+        let mut repair_packets: Vec<&EncodingPacket> = self.repair_packets.iter().collect();
+        // Sorted, so that the constraint matrix only depends on the set of received ESIs
+        repair_packets.sort_by_key(|packet| packet.payload_id.encoding_symbol_id());
+        let received_esis: Vec<u32> = (0..self.source_block_symbols)
+            .filter(|&i| self.source_symbols[i as usize].is_some())
+            .chain(
+                repair_packets
+                    .iter()
+                    .map(|packet| packet.payload_id.encoding_symbol_id()),
+            )
+            .collect();
+
+        if let Some(plan) = cache
+            .as_deref_mut()
+            .and_then(|cache| cache.get(self.source_block_symbols, &received_esis))
+        {
+            let mut d = create_d(
+                self.source_block_symbols,
+                &received_esis,
+                self.source_symbols.iter().flatten().cloned().chain(
+                    repair_packets
+                        .iter()
+                        .map(|packet| Symbol::new(packet.data.clone())),
+                ),
+                self.symbol_size as usize,
+            );
+            perform_ops(&plan.operations, &mut d);
+            let intermediate_symbols = self.check_consistency(d)?;
+            self.incremental = None;
+            return Ok(self.rebuild_source_block(&intermediate_symbols));
+        }
+
+        if let Some(ref mut incremental) = self.incremental {
+            let intermediate_symbols = incremental.solve().ok_or(DecodeFailure::SingularSystem)?;
+            // Kept until the solution is found to be consistent, so that a failed check can be
+            // retried once more symbols have been added to it
+            let intermediate_symbols = self.check_consistency(intermediate_symbols)?;
+            self.incremental = None;
+            return Ok(self.rebuild_source_block(&intermediate_symbols));
+        }
+
+        let d = create_d(
+            self.source_block_symbols,
+            &received_esis,
+            self.source_symbols.iter().flatten().cloned().chain(
+                repair_packets
+                    .iter()
+                    .map(|packet| Symbol::new(packet.data.clone())),
+            ),
+            self.symbol_size as usize,
+        );
+        let (intermediate_symbols, operations) = solve_intermediate_symbols(
+            self.source_block_symbols,
+            &received_esis,
+            d,
+            self.sparse_threshold,
+        );
+        let intermediate_symbols = intermediate_symbols.ok_or(DecodeFailure::SingularSystem)?;
+        if let (Some(cache), Some(operations)) = (cache, operations) {
+            cache.insert(SourceBlockDecodingPlan {
+                source_symbol_count: self.source_block_symbols,
+                received_esis,
+                operations,
+            });
+        }
+        let intermediate_symbols = self.check_consistency(intermediate_symbols)?;
+        Ok(self.rebuild_source_block(&intermediate_symbols))
+    }
+
+    // Verifies the intermediate symbols against every received symbol, if enabled. On a mismatch,
+    // isolates the inconsistent symbols, discards them, and returns the solution without them
+    fn check_consistency(
+        &mut self,
+        intermediate_symbols: Vec<Symbol>,
+    ) -> Result<Vec<Symbol>, DecodeFailure> {
+        if !self.consistency_check
+            || (self.satisfies_constraints(&intermediate_symbols)
+                && self
+                    .inconsistent_esis(&intermediate_symbols, &[])
+                    .is_empty())
+        {
+            return Ok(intermediate_symbols);
+        }
+        let (inconsistent, intermediate_symbols) = self
+            .isolate_inconsistent_symbols(&intermediate_symbols)
+            .ok_or(DecodeFailure::InconsistentSymbols)?;
+        self.discard_symbols(&inconsistent);
+        self.inconsistent_symbols.extend(inconsistent);
+        Ok(intermediate_symbols)
+    }
+
+    // Returns the ESIs of the received symbols, other than those excluded, which differ from the
+    // symbols generated from the intermediate symbols
+    fn inconsistent_esis(&self, intermediate_symbols: &[Symbol], excluded: &[u32]) -> Vec<u32> {
+        let lt_symbols = num_lt_symbols(self.source_block_symbols);
+        let pi_symbols = num_pi_symbols(self.source_block_symbols);
+        let sys_index = systematic_index(self.source_block_symbols);
+        let p1 = calculate_p1(self.source_block_symbols);
+        self.received_symbols()
+            .filter(|(encoding_symbol_id, _)| !excluded.contains(encoding_symbol_id))
+            .filter(|&(encoding_symbol_id, payload)| {
+                let generated = self.rebuild_source_symbol(
+                    intermediate_symbols,
+                    Self::internal_symbol_id(encoding_symbol_id, self.source_block_symbols),
+                    lt_symbols,
+                    pi_symbols,
+                    sys_index,
+                    p1,
+                );
+                generated.as_bytes() != payload
+            })
+            .map(|(encoding_symbol_id, _)| encoding_symbol_id)
+            .collect()
+    }
+
+    // Checks the padding symbols, and the LDPC and HDPC relations, which the solver only enforces
+    // for the rows it used. See section 5.3.3.4
+    fn satisfies_constraints(&self, intermediate_symbols: &[Symbol]) -> bool {
+        let extended_symbols = extended_source_block_symbols(self.source_block_symbols);
+        let lt_symbols = num_lt_symbols(self.source_block_symbols);
+        let pi_symbols = num_pi_symbols(self.source_block_symbols);
+        let sys_index = systematic_index(self.source_block_symbols);
+        let p1 = calculate_p1(self.source_block_symbols);
+        let zero = Symbol::zero(self.symbol_size);
+        let padding_consistent = (self.source_block_symbols..extended_symbols).all(|i| {
+            self.rebuild_source_symbol(
+                intermediate_symbols,
+                i,
+                lt_symbols,
+                pi_symbols,
+                sys_index,
+                p1,
+            ) == zero
+        });
+        if !padding_consistent {
+            return false;
+        }
+
         let s = num_ldpc_symbols(self.source_block_symbols) as usize;
         let h = num_hdpc_symbols(self.source_block_symbols) as usize;
+        let mut ldpc = vec![zero.clone(); s];
+        ldpc_entries(self.source_block_symbols, |row, col| {
+            ldpc[row] += &intermediate_symbols[col];
+        });
+        if ldpc.iter().any(|symbol| *symbol != zero) {
+            return false;
+        }
 
-        let mut encoded_isis = vec![];
-        // See section 5.3.3.4.2. There are S + H zero symbols to start the D vector
-        let mut d = vec![Symbol::zero(self.symbol_size); s + h];
-        for (i, source) in self.source_symbols.iter().enumerate() {
-            if let Some(symbol) = source {
-                encoded_isis.push(i as u32);
-                d.push(symbol.clone());
+        let hdpc_rows = generate_hdpc_rows(extended_symbols as usize, s, h);
+        (0..h).all(|row| {
+            let mut symbol = zero.clone();
+            for (col, intermediate_symbol) in intermediate_symbols.iter().enumerate() {
+                let scalar = hdpc_rows.get(row, col);
+                if scalar == Octet::one() {
+                    symbol += intermediate_symbol;
+                } else if scalar != Octet::zero() {
+                    symbol.fused_addassign_mul_scalar(intermediate_symbol, &scalar);
+                }
             }
+            symbol == zero
+        })
+    }
+
+    // Solves for the intermediate symbols with every received symbol, other than those excluded,
+    // and returns the solution if it is consistent with all of them. At least one surplus symbol
+    // must remain, so that the solution can be verified
+    fn solve_consistent_without(&self, excluded: &[u32]) -> Option<Vec<Symbol>> {
+        if self.received_esi.len() - excluded.len() <= self.source_block_symbols as usize {
+            return None;
         }
+        let mut received: Vec<(u32, &[u8])> = self
+            .received_symbols()
+            .filter(|(encoding_symbol_id, _)| !excluded.contains(encoding_symbol_id))
+            .collect();
+        received.sort_unstable_by_key(|(encoding_symbol_id, _)| *encoding_symbol_id);
+        let received_esis: Vec<u32> = received.iter().map(|(esi, _)| *esi).collect();
+        let d = create_d(
+            self.source_block_symbols,
+            &received_esis,
+            received
+                .iter()
+                .map(|(_, payload)| Symbol::new(payload.to_vec())),
+            self.symbol_size as usize,
+        );
+        let (intermediate_symbols, _) = solve_intermediate_symbols(
+            self.source_block_symbols,
+            &received_esis,
+            d,
+            self.sparse_threshold,
+        );
+        let intermediate_symbols = intermediate_symbols?;
+        (self.satisfies_constraints(&intermediate_symbols)
+            && self
+                .inconsistent_esis(&intermediate_symbols, excluded)
+                .is_empty())
+        .then_some(intermediate_symbols)
+    }
 
-        // Append the extended padding symbols
-        for i in self.source_block_symbols..num_extended_symbols {
-            encoded_isis.push(i);
-            d.push(Symbol::zero(self.symbol_size));
+    // Searches for the received symbols which are inconsistent with the others, returning their
+    // ESIs and the solution without them
+    fn isolate_inconsistent_symbols(
+        &self,
+        intermediate_symbols: &[Symbol],
+    ) -> Option<(Vec<u32>, Vec<Symbol>)> {
+        // A corrupted symbol which was not needed to solve only causes a mismatch of its own
+        let mismatches = self.inconsistent_esis(intermediate_symbols, &[]);
+        if !mismatches.is_empty()
+            && let Some(solution) = self.solve_consistent_without(&mismatches)
+        {
+            return Some((mismatches, solution));
         }
 
-        // Append the received repair symbols
-        for repair_packet in self.repair_packets.iter() {
-            // We need to convert from ESI to ISI
-            encoded_isis.push(repair_packet.payload_id.encoding_symbol_id() + num_padding_symbols);
-            d.push(Symbol::new(repair_packet.data.clone()));
+        // Otherwise, a corrupted symbol was used to solve, and the surplus symbols or the
+        // constraints mismatch instead. Exclude groups of symbols, as large as the surplus
+        // allows, until the rest are consistent, and then narrow the group down to a single symbol
+        let surplus = self.received_esi.len() - self.source_block_symbols as usize;
+        if surplus < 2 {
+            return None;
+        }
+        let mut candidates: Vec<u32> = self.received_esi.iter().copied().collect();
+        candidates.sort_unstable();
+        for group in candidates.chunks(surplus - 1) {
+            if let Some(solution) = self.solve_consistent_without(group) {
+                if group.len() > 1 {
+                    for &encoding_symbol_id in group {
+                        if let Some(solution) = self.solve_consistent_without(&[encoding_symbol_id])
+                        {
+                            return Some((vec![encoding_symbol_id], solution));
+                        }
+                    }
+                }
+                return Some((group.to_vec(), solution));
+            }
+        }
+        None
+    }
+
+    // Forgets received symbols, as if they had never been received
+    fn discard_symbols(&mut self, encoding_symbol_ids: &[u32]) {
+        for &encoding_symbol_id in encoding_symbol_ids {
+            self.received_esi.remove(&encoding_symbol_id);
+            if encoding_symbol_id < self.source_block_symbols {
+                self.source_symbols[encoding_symbol_id as usize] = None;
+                self.received_source_symbols -= 1;
+            }
         }
+        self.repair_packets.retain(|packet| {
+            !encoding_symbol_ids.contains(&packet.payload_id.encoding_symbol_id())
+        });
+    }
 
-        if num_extended_symbols >= self.sparse_threshold {
-            let (constraint_matrix, hdpc) = generate_constraint_matrix::<SparseBinaryMatrix>(
-                self.source_block_symbols,
-                &encoded_isis,
-            );
-            self.try_pi_decode(constraint_matrix, hdpc, d)
+    // Converts an ESI to an ISI, by skipping over the padding symbols. See section 5.3.1
+    fn internal_symbol_id(encoding_symbol_id: u32, source_block_symbols: u32) -> u32 {
+        if encoding_symbol_id < source_block_symbols {
+            encoding_symbol_id
         } else {
-            let (constraint_matrix, hdpc) = generate_constraint_matrix::<DenseBinaryMatrix>(
-                self.source_block_symbols,
-                &encoded_isis,
-            );
-            self.try_pi_decode(constraint_matrix, hdpc, d)
+            encoding_symbol_id + extended_source_block_symbols(source_block_symbols)
+                - source_block_symbols
         }
     }
 
@@ -333,6 +1829,210 @@ impl SourceBlockDecoder {
     }
 }
 
+/// A recorded schedule of symbol operations, which decodes a source block from a particular set
+/// of received ESIs.
+///
+/// Elimination only depends on which symbols were received, not on their contents, so the plan
+/// can be replayed on any other instance of the same block size and loss pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct SourceBlockDecodingPlan {
+    source_symbol_count: u32,
+    received_esis: Vec<u32>,
+    operations: Vec<SymbolOps>,
+}
+
+impl SourceBlockDecodingPlan {
+    /// Generates a plan for decoding a block of `source_symbol_count` symbols from the given
+    /// ESIs, or returns `None` if they do not determine the block. The ESIs may be in any order.
+    pub fn generate(
+        source_symbol_count: u32,
+        received_esis: &[u32],
+    ) -> Option<SourceBlockDecodingPlan> {
+        let mut received_esis = received_esis.to_vec();
+        received_esis.sort_unstable();
+        received_esis.dedup();
+        if received_esis.len() < source_symbol_count as usize {
+            return None;
+        }
+        // TODO: refactor pi_solver, so that we don't need this dummy data to generate a plan
+        let d = create_d(
+            source_symbol_count,
+            &received_esis,
+            iter::repeat_n(Symbol::zero(1usize), received_esis.len()),
+            1,
+        );
+        let (_, operations) = solve_intermediate_symbols(
+            source_symbol_count,
+            &received_esis,
+            d,
+            SPARSE_MATRIX_THRESHOLD,
+        );
+        Some(SourceBlockDecodingPlan {
+            source_symbol_count,
+            received_esis,
+            operations: operations?,
+        })
+    }
+
+    pub fn source_symbol_count(&self) -> u32 {
+        self.source_symbol_count
+    }
+
+    /// The ESIs this plan decodes from, in ascending order
+    pub fn received_esis(&self) -> &[u32] {
+        &self.received_esis
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        bytes += size_of::<u32>() * self.received_esis.len();
+        bytes += size_of::<SymbolOps>() * self.operations.len();
+        for op in self.operations.iter() {
+            if let SymbolOps::Reorder { order } = op {
+                bytes += size_of::<usize>() * order.len();
+            }
+        }
+        bytes
+    }
+}
+
+/// A bounded cache of [`SourceBlockDecodingPlan`]s, which evicts the least recently used plan
+/// once full.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodingPlanCache {
+    capacity: usize,
+    // Ordered from least to most recently used
+    plans: Vec<SourceBlockDecodingPlan>,
+}
+
+impl DecodingPlanCache {
+    pub fn new(capacity: usize) -> DecodingPlanCache {
+        DecodingPlanCache {
+            capacity,
+            plans: vec![],
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.plans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plans.is_empty()
+    }
+
+    /// Returns the plan for the given block size and received ESIs, which must be in ascending
+    /// order, and marks it as most recently used.
+    pub fn get(
+        &mut self,
+        source_symbol_count: u32,
+        received_esis: &[u32],
+    ) -> Option<&SourceBlockDecodingPlan> {
+        let index = self.plans.iter().position(|plan| {
+            plan.source_symbol_count == source_symbol_count && plan.received_esis == received_esis
+        })?;
+        let plan = self.plans.remove(index);
+        self.plans.push(plan);
+        self.plans.last()
+    }
+
+    /// Adds a plan, replacing any existing plan for the same block size and ESIs, and evicting
+    /// the least recently used plan if the cache is full.
+    pub fn insert(&mut self, plan: SourceBlockDecodingPlan) {
+        self.plans.retain(|existing| {
+            existing.source_symbol_count != plan.source_symbol_count
+                || existing.received_esis != plan.received_esis
+        });
+        if self.capacity == 0 {
+            return;
+        }
+        if self.plans.len() == self.capacity {
+            self.plans.remove(0);
+        }
+        self.plans.push(plan);
+    }
+
+    pub fn clear(&mut self) {
+        self.plans.clear();
+    }
+
+    /// Returns the approximate number of bytes used by the cached plans
+    pub fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self
+                .plans
+                .iter()
+                .map(SourceBlockDecodingPlan::size_in_bytes)
+                .sum::<usize>()
+    }
+}
+
+// Builds the D vector of section 5.3.3.4.2 from the symbols received for `received_esis`, which
+// must be in ascending order
+fn create_d(
+    source_block_symbols: u32,
+    received_esis: &[u32],
+    received_symbols: impl Iterator<Item = Symbol>,
+    symbol_size: usize,
+) -> Vec<Symbol> {
+    let num_extended_symbols = extended_source_block_symbols(source_block_symbols);
+    let s = num_ldpc_symbols(source_block_symbols) as usize;
+    let h = num_hdpc_symbols(source_block_symbols) as usize;
+
+    // There are S + H zero symbols to start the D vector
+    let mut d = vec![Symbol::zero(symbol_size); s + h];
+    let source = received_esis.partition_point(|&esi| esi < source_block_symbols);
+    let mut received_symbols = received_symbols;
+    d.extend(received_symbols.by_ref().take(source));
+    // Followed by the received source symbols, the extended padding symbols, and then the
+    // received repair symbols
+    d.extend(iter::repeat_n(
+        Symbol::zero(symbol_size),
+        (num_extended_symbols - source_block_symbols) as usize,
+    ));
+    d.extend(received_symbols);
+    d
+}
+
+// Solves for the intermediate symbols from a D vector built by create_d(), returning the
+// operations performed
+fn solve_intermediate_symbols(
+    source_block_symbols: u32,
+    received_esis: &[u32],
+    d: Vec<Symbol>,
+    sparse_threshold: u32,
+) -> (Option<Vec<Symbol>>, Option<Vec<SymbolOps>>) {
+    let num_extended_symbols = extended_source_block_symbols(source_block_symbols);
+    let num_padding_symbols = num_extended_symbols - source_block_symbols;
+    let source = received_esis.partition_point(|&esi| esi < source_block_symbols);
+    let encoded_isis: Vec<u32> = received_esis[..source]
+        .iter()
+        .copied()
+        .chain(source_block_symbols..num_extended_symbols)
+        // We need to convert from ESI to ISI
+        .chain(
+            received_esis[source..]
+                .iter()
+                .map(|esi| esi + num_padding_symbols),
+        )
+        .collect();
+
+    if num_extended_symbols >= sparse_threshold {
+        let (constraint_matrix, hdpc) =
+            generate_constraint_matrix::<SparseBinaryMatrix>(source_block_symbols, &encoded_isis);
+        fused_inverse_mul_symbols(constraint_matrix, hdpc, d, source_block_symbols)
+    } else {
+        let (constraint_matrix, hdpc) =
+            generate_constraint_matrix::<DenseBinaryMatrix>(source_block_symbols, &encoded_isis);
+        fused_inverse_mul_symbols(constraint_matrix, hdpc, d, source_block_symbols)
+    }
+}
+
 #[cfg(feature = "std")]
 #[cfg(test)]
 mod codec_tests {
@@ -346,17 +2046,17 @@ mod codec_tests {
     };
 
     use rand::Rng;
-    #[cfg(not(feature = "python"))]
     use rand::seq::SliceRandom;
 
     #[cfg(not(feature = "python"))]
     use crate::Decoder;
-    #[cfg(not(feature = "python"))]
-    use crate::{Encoder, EncoderBuilder};
     use crate::{
-        ObjectTransmissionInformation, SourceBlockDecoder, SourceBlockEncoder,
-        SourceBlockEncodingPlan,
+        BlockFailure, DecodeAttemptPolicy, DecodeFailure, DecoderConfig, DecodingPlanCache,
+        ObjectTransmissionInformation, SourceBlockDecoder, SourceBlockDecodingPlan,
+        SourceBlockEncoder, SourceBlockEncodingPlan,
     };
+    #[cfg(not(feature = "python"))]
+    use crate::{Encoder, EncoderBuilder, EncodingPacket, PayloadId, RaptorQError};
 
     #[cfg(not(feature = "python"))]
     #[test]
@@ -447,6 +2147,773 @@ mod codec_tests {
         assert_eq!(result.unwrap(), data);
     }
 
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn try_decode_invalid_packets() {
+        let data: Vec<u8> = (0..1000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 100, 2, 1, 1);
+        let encoder = Encoder::new(&data, config);
+        let mut decoder = Decoder::new(config);
+
+        assert_eq!(
+            decoder.try_decode(EncodingPacket::new(PayloadId::new(2, 0), vec![0; 100])),
+            Err(RaptorQError::InvalidSourceBlockNumber {
+                source_block_number: 2,
+                source_blocks: 2
+            })
+        );
+        assert_eq!(
+            decoder.try_decode(EncodingPacket::new(PayloadId::new(0, 0), vec![0; 99])),
+            Err(RaptorQError::SymbolSizeMismatch {
+                expected: 100,
+                actual: 99
+            })
+        );
+
+        let mut block_decoder = SourceBlockDecoder::new(1, &config, 500);
+        assert_eq!(
+            block_decoder.try_decode(iter::once(EncodingPacket::new(
+                PayloadId::new(0, 0),
+                vec![0; 100]
+            ))),
+            Err(RaptorQError::SourceBlockMismatch {
+                expected: 1,
+                actual: 0
+            })
+        );
+
+        let mut result = None;
+        for packet in encoder.get_encoded_packets(0) {
+            assert_eq!(result, None);
+            result = decoder.try_decode(packet).unwrap();
+        }
+        assert_eq!(result.unwrap(), data);
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn decode_block_trims_last_block() {
+        let data: Vec<u8> = (0..1042).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 3, 1, 8);
+        let encoder = Encoder::new(&data, config);
+        let mut packets = encoder.get_encoded_packets(4);
+        packets.shuffle(&mut rand::rng());
+
+        let mut decoder = Decoder::new(config);
+        let mut blocks = vec![None; 3];
+        for packet in packets {
+            if let Some((sbn, block)) = decoder.decode_block(packet) {
+                assert!(blocks[sbn as usize].is_none());
+                blocks[sbn as usize] = Some(block);
+            }
+        }
+
+        let result: Vec<u8> = blocks.into_iter().flat_map(Option::unwrap).collect();
+        assert_eq!(result, data);
+        assert_eq!(decoder.get_result().unwrap(), data);
+    }
+
+    #[test]
+    fn block_status() {
+        let symbol_size = 8;
+        let symbol_count = 10;
+        let data: Vec<u8> = (0..symbol_size * symbol_count)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+        let mut decoder = SourceBlockDecoder::new(0, &config, data.len() as u64);
+
+        let status = decoder.status();
+        assert_eq!(status.source_symbols(), 10);
+        assert_eq!(status.symbols_needed(), 10);
+        assert!(!status.is_decoded());
+
+        let source_packets = encoder.source_packets();
+        decoder.decode(source_packets[..4].iter().cloned());
+        decoder.decode(iter::once(source_packets[0].clone()));
+        decoder.decode(encoder.repair_packets(0, 3));
+
+        let status = decoder.status();
+        assert_eq!(status.received_source_symbols(), 4);
+        assert_eq!(status.received_repair_symbols(), 3);
+        assert_eq!(status.received_symbols(), 7);
+        assert_eq!(status.duplicate_symbols(), 1);
+        assert_eq!(status.symbols_needed(), 3);
+        assert!(!status.attempt_pending());
+
+        let mut result = None;
+        for packet in encoder.repair_packets(3, 10) {
+            result = decoder.decode(iter::once(packet));
+            if result.is_some() {
+                break;
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+        assert_eq!(decoder.status().symbols_needed(), 0);
+        assert!(decoder.status().is_decoded());
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn object_status() {
+        let data: Vec<u8> = (0..1000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 100, 2, 1, 1);
+        let encoder = Encoder::new(&data, config);
+        let mut decoder = Decoder::new(config);
+        assert_eq!(decoder.status().symbols_needed(), 10);
+        assert_eq!(decoder.block_status(2), None);
+
+        let packets = encoder.get_block_encoders()[0].source_packets();
+        for packet in packets {
+            decoder.decode(packet);
+        }
+
+        let status = decoder.status();
+        assert_eq!(status.source_symbols(), 10);
+        assert_eq!(status.received_source_symbols(), 5);
+        assert_eq!(status.symbols_needed(), 5);
+        assert!(!status.is_decoded());
+        assert!(decoder.block_status(0).unwrap().is_decoded());
+        assert_eq!(decoder.block_status(1).unwrap().symbols_needed(), 5);
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn decode_packets() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 4, 1, 8);
+        let encoder = Encoder::new(&data, config);
+
+        let mut packets = encoder.get_encoded_packets(10);
+        packets.retain(|packet| packet.payload_id().encoding_symbol_id() % 5 != 0);
+        packets.shuffle(&mut rand::rng());
+        let last = packets.split_off(packets.len() / 2);
+
+        let mut decoder = Decoder::new(config);
+        assert_eq!(decoder.decode_packets(packets), None);
+        assert_eq!(decoder.decode_packets(last).unwrap(), data);
+    }
+
+    #[test]
+    fn decoding_plan_cache() {
+        let symbol_size = 8;
+        let symbol_count = 50;
+        let elements = symbol_size * symbol_count;
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let mut cache = DecodingPlanCache::new(2);
+        let empty_usage = cache.memory_usage();
+
+        let mut plan = None;
+        for _ in 0..3 {
+            let data: Vec<u8> = (0..elements).map(|_| rand::rng().random()).collect();
+            let encoder = SourceBlockEncoder::new(0, &config, &data);
+            // Same loss pattern every time, with the repair symbols arriving in a different order
+            let mut packets = encoder.source_packets();
+            packets.retain(|packet| packet.payload_id().encoding_symbol_id() % 4 != 0);
+            let mut repair = encoder.repair_packets(0, 15);
+            repair.shuffle(&mut rand::rng());
+            packets.extend(repair);
+            let received_esis: Vec<u32> = packets
+                .iter()
+                .map(|packet| packet.payload_id().encoding_symbol_id())
+                .collect();
+
+            let mut decoder = SourceBlockDecoder::new(0, &config, elements as u64);
+            let result = decoder.decode_with_plan_cache(packets, &mut cache);
+            assert_eq!(result.unwrap(), data);
+            assert_eq!(cache.len(), 1);
+            assert!(cache.memory_usage() > empty_usage);
+
+            let generated =
+                SourceBlockDecodingPlan::generate(symbol_count as u32, &received_esis).unwrap();
+            assert_eq!(
+                cache.get(symbol_count as u32, generated.received_esis()),
+                Some(&generated)
+            );
+            plan = Some(generated);
+        }
+
+        // Least recently used plans are evicted
+        let plan = plan.unwrap();
+        let other = SourceBlockDecodingPlan::generate(10, &(0..12).collect::<Vec<u32>>()).unwrap();
+        let another =
+            SourceBlockDecodingPlan::generate(10, &(1..12).collect::<Vec<u32>>()).unwrap();
+        cache.insert(other.clone());
+        assert!(
+            cache
+                .get(plan.source_symbol_count(), plan.received_esis())
+                .is_some()
+        );
+        cache.insert(another.clone());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(10, other.received_esis()).is_none());
+        assert_eq!(cache.get(10, another.received_esis()), Some(&another));
+        assert!(SourceBlockDecodingPlan::generate(10, &[0, 1, 2]).is_none());
+    }
+
+    #[test]
+    fn repair_incremental() {
+        for symbol_count in 1..=50 {
+            let symbol_size = 8;
+            let elements = symbol_size * symbol_count;
+            let data: Vec<u8> = (0..elements).map(|_| rand::rng().random()).collect();
+            let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+            let encoder = SourceBlockEncoder::new(1, &config, &data);
+
+            let mut decoder = SourceBlockDecoder::new(1, &config, elements as u64);
+            // Enable after some packets have already been received, to cover replaying them
+            decoder.decode(encoder.source_packets().into_iter().step_by(3));
+            decoder.set_incremental_decoding(true);
+
+            let mut result = None;
+            for packet in encoder.repair_packets(0, symbol_count as u32 + 4) {
+                result = decoder.decode(iter::once(packet));
+                if result.is_some() {
+                    break;
+                }
+            }
+            assert_eq!(result.unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn explicit_attempt_policy() {
+        let symbol_size = 8;
+        let symbol_count = 20;
+        let elements = symbol_size * symbol_count;
+        let data: Vec<u8> = (0..elements).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_attempt_policy(DecodeAttemptPolicy::Explicit);
+        let mut decoder =
+            SourceBlockDecoder::with_decoder_config(0, &config, elements as u64, &decoder_config);
+
+        let mut packets = encoder.repair_packets(0, symbol_count as u32 + 5);
+        let remaining = packets.split_off(symbol_count as usize - 1);
+        assert_eq!(decoder.decode(packets), None);
+        assert_eq!(
+            decoder.try_finish(),
+            Err(DecodeFailure::InsufficientSymbols {
+                received: symbol_count as u32 - 1,
+                needed: symbol_count as u32,
+            })
+        );
+        let status = decoder.status();
+        assert_eq!(
+            status.last_failure(),
+            Some(DecodeFailure::InsufficientSymbols {
+                received: symbol_count as u32 - 1,
+                needed: symbol_count as u32,
+            })
+        );
+        assert_eq!(status.failed_attempts(), 0);
+
+        assert_eq!(decoder.decode(remaining), None);
+        assert_eq!(decoder.try_finish(), Ok(data));
+        assert!(decoder.status().is_decoded());
+    }
+
+    #[test]
+    fn status_before_attempt() {
+        let symbol_size = 8;
+        let symbol_count = 20;
+        let elements = symbol_size * symbol_count;
+        let data: Vec<u8> = (0..elements).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_attempt_policy(DecodeAttemptPolicy::Explicit);
+        let mut decoder =
+            SourceBlockDecoder::with_decoder_config(0, &config, elements as u64, &decoder_config);
+
+        assert_eq!(
+            decoder.decode(encoder.repair_packets(0, symbol_count as u32)),
+            None
+        );
+        let status = decoder.status();
+        assert_eq!(status.received_symbols(), symbol_count as u32);
+        assert_eq!(status.failed_attempts(), 0);
+        assert_eq!(status.symbols_needed(), 0);
+        assert!(status.attempt_pending());
+        assert_eq!(status.last_failure(), None);
+        assert!(!status.is_decoded());
+
+        // Once attempted, decoding is no longer pending, whether it succeeded or not
+        decoder.try_finish().ok();
+        let status = decoder.status();
+        assert!(!status.attempt_pending());
+        assert_eq!(status.symbols_needed(), u32::from(!status.is_decoded()));
+    }
+
+    #[test]
+    fn status_of_empty_block() {
+        let config = ObjectTransmissionInformation::new(0, 8, 0, 1, 1);
+        let mut decoder = SourceBlockDecoder::new(0, &config, 0);
+        assert_eq!(decoder.status().source_symbols(), 0);
+        assert_eq!(decoder.status().symbols_needed(), 0);
+        assert_eq!(decoder.finish(), Ok(vec![]));
+        assert_eq!(decoder.status().symbols_needed(), 0);
+        assert!(decoder.status().is_decoded());
+    }
+
+    #[test]
+    fn overhead_attempt_policy() {
+        let symbol_size = 8;
+        let symbol_count = 20;
+        let elements = symbol_size * symbol_count;
+        let data: Vec<u8> = (0..elements).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+
+        for policy in [
+            DecodeAttemptPolicy::Overhead(3),
+            DecodeAttemptPolicy::ExponentialBackoff(3),
+        ] {
+            let mut decoder_config = DecoderConfig::new();
+            decoder_config.set_attempt_policy(policy);
+            let mut decoder = SourceBlockDecoder::with_decoder_config(
+                0,
+                &config,
+                elements as u64,
+                &decoder_config,
+            );
+
+            let mut packets = encoder.repair_packets(0, symbol_count as u32 + 3);
+            let last = packets.pop().unwrap();
+            assert_eq!(decoder.decode(packets), None);
+            assert_eq!(decoder.status().last_failure(), None);
+            assert_eq!(decoder.decode(iter::once(last)).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn source_symbols_ignore_attempt_policy() {
+        let symbol_size = 8;
+        let symbol_count = 20;
+        let elements = symbol_size * symbol_count;
+        let data: Vec<u8> = (0..elements).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_attempt_policy(DecodeAttemptPolicy::Explicit);
+        let mut decoder =
+            SourceBlockDecoder::with_decoder_config(0, &config, elements as u64, &decoder_config);
+        assert_eq!(decoder.decode(encoder.source_packets()).unwrap(), data);
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn object_try_finish() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let encoder = Encoder::with_defaults(&data, 64);
+
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_attempt_policy(DecodeAttemptPolicy::Explicit);
+        let mut decoder = Decoder::with_decoder_config(encoder.get_config(), &decoder_config);
+        assert!(matches!(
+            decoder.try_finish(),
+            Err(DecodeFailure::InsufficientSymbols { received: 0, .. })
+        ));
+
+        let mut result = None;
+        for packet in encoder.get_encoded_packets(20) {
+            // Skip source symbols, so that decoding requires solving
+            if packet.payload_id().encoding_symbol_id() < 15 {
+                continue;
+            }
+            result = decoder.decode(packet);
+        }
+        assert_eq!(result, None);
+        assert_eq!(decoder.try_finish().unwrap(), data);
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn object_finish() {
+        let data: Vec<u8> = (0..3000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 16, 3, 1, 1);
+        let encoder = Encoder::new(&data, config);
+        let blocks = encoder.get_block_encoders();
+        let mut decoder = Decoder::new(config);
+
+        decoder.decode_packets(blocks[0].source_packets());
+        decoder.decode_packets(blocks[1].source_packets().into_iter().take(40));
+        let failures = decoder.finish().unwrap_err();
+        assert_eq!(
+            failures,
+            vec![
+                BlockFailure::InsufficientSymbols {
+                    source_block_number: 1,
+                    received: 40,
+                    needed: 63
+                },
+                BlockFailure::InsufficientSymbols {
+                    source_block_number: 2,
+                    received: 0,
+                    needed: 62
+                },
+            ]
+        );
+        assert_eq!(
+            failures[0].to_string(),
+            "source block 1 received 40 of 63 symbols, 23 more needed"
+        );
+        assert_eq!(
+            decoder.try_finish(),
+            Err(DecodeFailure::InsufficientSymbols {
+                received: 40,
+                needed: 63
+            })
+        );
+        assert_eq!(decoder.decoded_block(0).unwrap(), data[..1008]);
+        assert_eq!(decoder.decoded_block(1), None);
+        assert_eq!(decoder.decoded_block(3), None);
+
+        decoder.decode_packets(blocks[1].repair_packets(0, 23));
+        decoder.decode_packets(blocks[2].source_packets());
+        assert_eq!(decoder.finish().unwrap(), data);
+        assert_eq!(decoder.decoded_block(2).unwrap(), data[2016..]);
+    }
+
+    #[test]
+    fn partial_block_result() {
+        let symbol_size = 8;
+        let symbol_count = 10;
+        let data: Vec<u8> = (0..symbol_size * symbol_count)
+            .map(|_| rand::rng().random_range(1..=255))
+            .collect();
+        // Two sub-blocks of 4 byte sub-symbols
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 2, 4);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+        let mut decoder = SourceBlockDecoder::new(0, &config, data.len() as u64);
+
+        let empty = decoder.partial_result();
+        assert_eq!(empty.data(), vec![0; data.len()]);
+        assert_eq!(empty.present_symbols(), 0);
+        assert_eq!(empty.holes(), &[(0, data.len())]);
+
+        let mut packets = encoder.source_packets();
+        packets.drain(3..5);
+        assert_eq!(decoder.decode(packets), None);
+        let partial = decoder.partial_result();
+        assert_eq!(partial.symbol_count(), 10);
+        assert_eq!(partial.present_symbols(), 8);
+        assert_eq!(partial.bitmap(), &[0b1110_0111, 0b11]);
+        assert!(!partial.is_present(3) && partial.is_present(5) && !partial.is_present(10));
+        assert_eq!(partial.holes(), &[(12, 20), (52, 60)]);
+        for (i, byte) in partial.data().iter().enumerate() {
+            let missing = (12..20).contains(&i) || (52..60).contains(&i);
+            assert_eq!(*byte, if missing { 0 } else { data[i] });
+        }
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn partial_object_result() {
+        let data: Vec<u8> = (0..3_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 3, 1, 8);
+        let encoder = Encoder::new(&data, config);
+        let blocks = encoder.get_block_encoders();
+        let mut decoder = Decoder::new(config);
+
+        decoder.decode_packets(blocks[0].source_packets());
+        let mut packets = blocks[2].source_packets();
+        // The last symbol is padded beyond the end of the object
+        packets.pop();
+        packets.remove(0);
+        decoder.decode_packets(packets);
+        let block_length = blocks[0].source_packets().len() * 64;
+        assert_eq!(block_length, 1024);
+
+        let partial = decoder.partial_result();
+        assert_eq!(partial.symbol_count(), 47);
+        assert_eq!(partial.present_symbols(), 16 + 13);
+        assert!(!partial.is_complete());
+        assert_eq!(partial.holes(), &[(1024, 2112), (2944, 3000)]);
+        assert_eq!(partial.data().len(), data.len());
+        assert_eq!(partial.data()[..1024], data[..1024]);
+        assert_eq!(partial.data()[2112..2944], data[2112..2944]);
+
+        decoder.decode_packets(blocks[1].source_packets());
+        decoder.decode_packets(blocks[2].source_packets());
+        let complete = decoder.partial_result();
+        assert!(complete.is_complete());
+        assert!(complete.holes().is_empty());
+        assert_eq!(complete.into_data(), data);
+    }
+
+    #[test]
+    fn consistency_check() {
+        let symbol_size = 16;
+        let symbol_count = 50;
+        let data: Vec<u8> = (0..symbol_size * symbol_count)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_attempt_policy(DecodeAttemptPolicy::Explicit);
+
+        // Five surplus symbols, one of which is a corrupted source symbol
+        let mut packets = encoder.source_packets();
+        packets.drain(..5);
+        packets.extend(encoder.repair_packets(0, 10));
+        let mut corrupted = packets[7].clone();
+        let corrupted_esi = corrupted.payload_id().encoding_symbol_id();
+        corrupted.data[3] ^= 0x40;
+        packets[7] = corrupted;
+
+        let mut unchecked =
+            SourceBlockDecoder::with_decoder_config(0, &config, data.len() as u64, &decoder_config);
+        unchecked.decode(packets.clone());
+        assert_ne!(unchecked.try_finish().unwrap(), data);
+
+        decoder_config.set_consistency_check(true);
+        let mut decoder =
+            SourceBlockDecoder::with_decoder_config(0, &config, data.len() as u64, &decoder_config);
+        decoder.decode(packets.clone());
+        assert_eq!(decoder.try_finish().unwrap(), data);
+        assert_eq!(decoder.inconsistent_symbols(), &[corrupted_esi]);
+        assert_eq!(decoder.status().inconsistent_symbols(), 1);
+        assert_eq!(decoder.status().received_symbols(), 54);
+
+        // With a single surplus symbol, the corruption is detected but cannot be isolated
+        let mut decoder =
+            SourceBlockDecoder::with_decoder_config(0, &config, data.len() as u64, &decoder_config);
+        decoder.decode(packets[..51].iter().cloned());
+        assert_eq!(
+            decoder.try_finish(),
+            Err(DecodeFailure::InconsistentSymbols)
+        );
+        assert_eq!(decoder.status().failed_attempts(), 1);
+        decoder.decode(packets[51..].iter().cloned());
+        assert_eq!(decoder.try_finish().unwrap(), data);
+
+        // A failed check keeps the incremental state, which is only dropped once decoded
+        decoder_config.set_incremental_decoding(true);
+        let mut decoder =
+            SourceBlockDecoder::with_decoder_config(0, &config, data.len() as u64, &decoder_config);
+        decoder.decode(packets[..51].iter().cloned());
+        assert_eq!(
+            decoder.try_finish(),
+            Err(DecodeFailure::InconsistentSymbols)
+        );
+        assert!(decoder.incremental.is_some());
+        decoder.decode(packets[51..].iter().cloned());
+        assert_eq!(decoder.try_finish().unwrap(), data);
+        assert!(decoder.incremental.is_none());
+    }
+
+    #[test]
+    fn retained_symbol_limit() {
+        let symbol_size = 8;
+        let symbol_count = 20;
+        let elements = symbol_size * symbol_count;
+        let data: Vec<u8> = (0..elements).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_attempt_policy(DecodeAttemptPolicy::Explicit);
+        decoder_config.set_max_retained_overhead(Some(2));
+        let mut decoder =
+            SourceBlockDecoder::with_decoder_config(0, &config, elements as u64, &decoder_config);
+
+        let mut packets = encoder.repair_packets(0, symbol_count as u32 + 3);
+        let last = packets.pop().unwrap();
+        assert_eq!(decoder.try_decode(packets.clone()), Ok(None));
+        assert_eq!(
+            decoder.try_decode(iter::once(last.clone())),
+            Err(RaptorQError::RetainedSymbolLimit {
+                source_block_number: 0,
+                limit: symbol_count as u32 + 2,
+            })
+        );
+        assert_eq!(
+            decoder.status().received_repair_symbols(),
+            symbol_count as u32 + 2
+        );
+        // Duplicates and source symbols do not count against the limit
+        assert_eq!(decoder.try_decode(packets), Ok(None));
+        assert_eq!(
+            decoder.try_decode(encoder.source_packets()).unwrap(),
+            Some(data)
+        );
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn active_block_limit() {
+        let data: Vec<u8> = (0..3_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 3, 1, 8);
+        let encoder = Encoder::new(&data, config);
+
+        let mut decoder_config = DecoderConfig::new();
+        decoder_config.set_incremental_decoding(true);
+        decoder_config.set_max_active_blocks(Some(1));
+        let mut decoder = Decoder::with_decoder_config(config, &decoder_config);
+        // Blocks allocate their symbols, and incremental decoding state, on the first packet
+        let empty_usage = decoder.memory_usage();
+
+        let blocks = encoder.get_block_encoders();
+        let mut first_block = blocks[0].source_packets();
+        let second_block = blocks[1].source_packets();
+        assert_eq!(decoder.try_decode(first_block.pop().unwrap()), Ok(None));
+        assert!(decoder.memory_usage() > empty_usage);
+        assert_eq!(
+            decoder.try_decode(second_block[0].clone()),
+            Err(RaptorQError::ActiveBlockLimit {
+                source_block_number: 1,
+                limit: 1,
+            })
+        );
+        assert_eq!(decoder.decode_block(second_block[0].clone()), None);
+        assert_eq!(decoder.block_status(1).unwrap().received_symbols(), 0);
+
+        // Once the first block is decoded, the second can start
+        for packet in first_block {
+            decoder.try_decode(packet).unwrap();
+        }
+        assert!(decoder.block_status(0).unwrap().is_decoded());
+        assert_eq!(decoder.decode_packets(second_block), None);
+        assert!(decoder.block_status(1).unwrap().is_decoded());
+        assert_eq!(decoder.status().rejected_packets().active_block_limit(), 2);
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn rejected_packets() {
+        let data: Vec<u8> = (0..3_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 2, 1, 8);
+        let encoder = Encoder::new(&data, config);
+        let mut packets = encoder.get_encoded_packets(0);
+        let mut decoder = Decoder::new(config);
+
+        // Invalid packets are dropped by decode(), instead of panicking or corrupting the block
+        let out_of_range = EncodingPacket::new(PayloadId::new(5, 0), vec![0; 64]);
+        assert_eq!(decoder.decode(out_of_range.clone()), None);
+        assert_eq!(decoder.decode_block(out_of_range.clone()), None);
+        let truncated = EncodingPacket::new(PayloadId::new(0, 1), vec![0; 63]);
+        assert_eq!(decoder.decode(truncated.clone()), None);
+        assert_eq!(
+            decoder.try_decode(out_of_range.clone()),
+            Err(RaptorQError::InvalidSourceBlockNumber {
+                source_block_number: 5,
+                source_blocks: 2
+            })
+        );
+        assert_eq!(
+            decoder.try_decode_block(truncated.clone()),
+            Err(RaptorQError::SymbolSizeMismatch {
+                expected: 64,
+                actual: 63
+            })
+        );
+        assert_eq!(
+            decoder.decode_packets([out_of_range, truncated.clone(), packets.remove(1)]),
+            None
+        );
+
+        let status = decoder.status();
+        assert_eq!(status.rejected_packets().invalid_source_block_number(), 4);
+        assert_eq!(status.rejected_packets().symbol_size_mismatch(), 3);
+        assert_eq!(status.rejected_packets().total(), 7);
+        assert_eq!(status.received_symbols(), 1);
+        assert_eq!(
+            decoder
+                .block_status(0)
+                .unwrap()
+                .rejected_packets()
+                .symbol_size_mismatch(),
+            3
+        );
+        assert_eq!(
+            decoder.block_status(1).unwrap().rejected_packets().total(),
+            0
+        );
+
+        let mut block_decoder = SourceBlockDecoder::new(1, &config, 1500);
+        assert_eq!(block_decoder.decode([truncated]), None);
+        assert_eq!(
+            block_decoder
+                .status()
+                .rejected_packets()
+                .invalid_source_block_number(),
+            1
+        );
+
+        assert_eq!(decoder.decode_packets(packets), Some(data));
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn add_new_packet_rejects_invalid_packets() {
+        let data: Vec<u8> = (0..3_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 2, 1, 8);
+        let encoder = Encoder::new(&data, config);
+        let mut decoder = Decoder::new(config);
+
+        decoder.add_new_packet(EncodingPacket::new(PayloadId::new(5, 0), vec![0; 64]));
+        decoder.add_new_packet(EncodingPacket::new(PayloadId::new(0, 1), vec![0; 63]));
+        let rejected_packets = decoder.status().rejected_packets();
+        assert_eq!(rejected_packets.invalid_source_block_number(), 1);
+        assert_eq!(rejected_packets.symbol_size_mismatch(), 1);
+        assert_eq!(decoder.status().received_symbols(), 0);
+
+        for packet in encoder.get_encoded_packets(0) {
+            decoder.add_new_packet(packet);
+        }
+        assert_eq!(decoder.get_result(), Some(data));
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn deserialize_baseline_decoder() {
+        let data: Vec<u8> = (0..1000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 100, 2, 1, 1);
+        let encoder = Encoder::new(&data, config);
+        let mut decoder = Decoder::new(config);
+        let mut packets = encoder.get_encoded_packets(2);
+        for packet in packets.drain(..3) {
+            decoder.decode(packet);
+        }
+
+        // Drop the fields which earlier versions did not serialize
+        let mut baseline = serde_json::to_value(&decoder).unwrap();
+        let object = baseline.as_object_mut().unwrap();
+        object.remove("max_active_blocks");
+        object.remove("rejected_packets");
+        for block_decoder in object["block_decoders"].as_array_mut().unwrap() {
+            let fields = block_decoder.as_object_mut().unwrap();
+            for field in [
+                "duplicate_symbols",
+                "incremental_decoding",
+                "incremental",
+                "attempt_policy",
+                "max_retained_overhead",
+                "consistency_check",
+                "inconsistent_symbols",
+                "rejected_packets",
+                "next_attempt",
+                "failed_attempts",
+                "failed_attempt_symbols",
+                "last_failure",
+            ] {
+                assert!(fields.remove(field).is_some(), "{field}");
+            }
+        }
+
+        let mut restored: Decoder = serde_json::from_value(baseline).unwrap();
+        assert_eq!(restored, decoder);
+        assert_eq!(restored.decode_packets(packets), Some(data));
+    }
+
     #[test]
     fn round_trip_dense() {
         round_trip(99_999, 100, false);
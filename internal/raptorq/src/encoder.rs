@@ -6,15 +6,21 @@ use alloc::vec::Vec;
 
 use crate::ObjectTransmissionInformation;
 use crate::base::EncodingPacket;
+use crate::base::PAYLOAD_ID_SIZE;
 use crate::base::PayloadId;
 use crate::base::intermediate_tuple;
 use crate::base::partition;
 use crate::constraint_matrix::generate_constraint_matrix;
+use crate::error::RaptorQError;
 use crate::matrix::DenseBinaryMatrix;
-use crate::operation_vector::{SymbolOps, perform_op};
+use crate::octets::add_assign;
+use crate::operation_vector::{SymbolOps, perform_ops};
 use crate::pi_solver::fused_inverse_mul_symbols;
+use crate::plan_store::PlanStore;
 use crate::sparse_matrix::SparseBinaryMatrix;
 use crate::symbol::Symbol;
+#[cfg(feature = "serde_support")]
+use crate::systematic_constants::MAX_SOURCE_SYMBOLS_PER_BLOCK;
 use crate::systematic_constants::extended_source_block_symbols;
 use crate::systematic_constants::num_hdpc_symbols;
 use crate::systematic_constants::num_intermediate_symbols;
@@ -23,6 +29,8 @@ use crate::systematic_constants::num_lt_symbols;
 use crate::systematic_constants::num_pi_symbols;
 use crate::systematic_constants::{calculate_p1, systematic_index};
 use crate::util::int_div_ceil;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
@@ -33,6 +41,21 @@ pub const SPARSE_MATRIX_THRESHOLD: u32 = 250;
 pub struct EncoderBuilder {
     decoder_memory_requirement: u64,
     max_packet_size: u16,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    alignment: Option<u8>,
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    repair_overhead_percent: u32,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default = "default_max_source_blocks")
+    )]
+    max_source_blocks: u8,
+}
+
+// Limit on source blocks of builders serialized before it was configurable
+#[cfg(feature = "serde_support")]
+fn default_max_source_blocks() -> u8 {
+    u8::MAX
 }
 
 impl EncoderBuilder {
@@ -40,25 +63,131 @@ impl EncoderBuilder {
         EncoderBuilder {
             decoder_memory_requirement: 10 * 1024 * 1024,
             max_packet_size: 1024,
+            alignment: None,
+            repair_overhead_percent: 0,
+            max_source_blocks: u8::MAX,
         }
     }
 
+    /// Sets WS, the maximum size of a sub-block that the decoder can hold in working memory.
+    /// See section 4.3
     pub fn set_decoder_memory_requirement(&mut self, bytes: u64) {
         self.decoder_memory_requirement = bytes;
     }
 
+    /// Sets P', the maximum payload size. The symbol size is the largest multiple of the
+    /// alignment that fits in it
     pub fn set_max_packet_size(&mut self, bytes: u16) {
         self.max_packet_size = bytes;
     }
 
-    pub fn build(&self, data: &[u8]) -> Encoder {
-        let config = ObjectTransmissionInformation::generate_encoding_parameters(
-            data.len() as u64,
+    /// Sets Al, the symbol alignment. By default, 8 bytes is used if the maximum packet size is
+    /// at least 64 bytes, and otherwise 1 byte
+    pub fn set_alignment(&mut self, bytes: u8) {
+        self.alignment = Some(bytes);
+    }
+
+    /// Sets the number of repair symbols to generate for each source block, as a percentage of its
+    /// source symbols
+    pub fn set_repair_overhead(&mut self, percent: u32) {
+        self.repair_overhead_percent = percent;
+    }
+
+    /// Sets the maximum number of source blocks the object may be split into
+    pub fn set_max_source_blocks(&mut self, blocks: u8) {
+        self.max_source_blocks = blocks;
+    }
+
+    /// Derives the encoding parameters for an object of `transfer_length` bytes, or explains why
+    /// the constraints cannot be met
+    pub fn try_parameters(&self, transfer_length: u64) -> Result<EncodingParameters, RaptorQError> {
+        let alignment = self
+            .alignment
+            .unwrap_or(if self.max_packet_size >= 8 * 8 { 8 } else { 1 });
+        // Use the lower bound on the sub-symbol size recommended in section 4.3, of 8 * Al, unless
+        // the symbols are too small to be sub-blocked at all
+        let symbol_size = self.max_packet_size - self.max_packet_size % alignment.max(1) as u16;
+        let min_sub_symbol_size = if symbol_size >= 8 * 8 && symbol_size >= 8 * alignment as u16 {
+            8 * alignment as u16
+        } else {
+            alignment as u16
+        };
+        let config = ObjectTransmissionInformation::try_generate_encoding_parameters(
+            transfer_length,
             self.max_packet_size,
+            alignment,
+            min_sub_symbol_size,
             self.decoder_memory_requirement,
+            self.max_source_blocks,
+        )?;
+
+        let kt = int_div_ceil(config.transfer_length(), config.symbol_size() as u64);
+        let (kl, ks, zl, zs) = partition(kt, config.source_blocks());
+        let mut source_symbols_per_block = vec![kl; zl as usize];
+        source_symbols_per_block.extend(vec![ks; zs as usize]);
+
+        let (tl, ts, nl, ns) = partition(
+            (config.symbol_size() / alignment as u16) as u32,
+            config.sub_blocks(),
         );
+        let mut sub_symbol_sizes = vec![(tl * alignment as u32) as u16; nl as usize];
+        sub_symbol_sizes.extend(vec![(ts * alignment as u32) as u16; ns as usize]);
+
+        let repair_packets_per_block =
+            (kl as u64 * self.repair_overhead_percent as u64).div_ceil(100);
+        if kl as u64 + repair_packets_per_block > MAX_ENCODING_SYMBOL_ID as u64 + 1 {
+            return Err(RaptorQError::InvalidEncodingParameters(
+                "repair overhead exceeds the encoding symbol ID space",
+            ));
+        }
 
-        Encoder::new(data, config)
+        Ok(EncodingParameters {
+            config,
+            source_symbols_per_block,
+            sub_symbol_sizes,
+            repair_packets_per_block: repair_packets_per_block as u32,
+        })
+    }
+
+    pub fn try_build(&self, data: &[u8]) -> Result<Encoder, RaptorQError> {
+        let parameters = self.try_parameters(data.len() as u64)?;
+        Ok(Encoder::new(data, parameters.config))
+    }
+
+    /// Like [`EncoderBuilder::try_build`], but panics if the constraints cannot be met
+    pub fn build(&self, data: &[u8]) -> Encoder {
+        self.try_build(data).unwrap()
+    }
+}
+
+/// Parameters derived by [`EncoderBuilder::try_parameters`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodingParameters {
+    config: ObjectTransmissionInformation,
+    source_symbols_per_block: Vec<u32>,
+    sub_symbol_sizes: Vec<u16>,
+    repair_packets_per_block: u32,
+}
+
+impl EncodingParameters {
+    pub fn config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Returns K, the number of source symbols, of each source block
+    pub fn source_symbols_per_block(&self) -> &[u32] {
+        &self.source_symbols_per_block
+    }
+
+    /// Returns the size, in bytes, of the sub-symbols of each sub-block
+    pub fn sub_symbol_sizes(&self) -> &[u16] {
+        &self.sub_symbol_sizes
+    }
+
+    /// Returns the number of repair packets to generate for each source block, to meet the target
+    /// repair overhead. Suitable for [`Encoder::get_encoded_packets`]
+    pub fn repair_packets_per_block(&self) -> u32 {
+        self.repair_packets_per_block
     }
 }
 
@@ -69,29 +198,36 @@ pub fn calculate_block_offsets(
     config: &ObjectTransmissionInformation,
 ) -> Vec<(usize, usize)> {
     let kt = int_div_ceil(config.transfer_length(), config.symbol_size() as u64);
+    object_block_offsets(config)
+        .into_iter()
+        .map(|(start, end)| {
+            if end as usize > data.len() {
+                // Should only be possible when Kt * T > F. See third to last paragraph in section 4.4.1.2
+                assert!(kt as usize * config.symbol_size() as usize > data.len());
+            }
+            (start as usize, end as usize)
+        })
+        .collect()
+}
+
+// Same as calculate_block_offsets(), but for an object which is not in memory
+pub(crate) fn object_block_offsets(config: &ObjectTransmissionInformation) -> Vec<(u64, u64)> {
+    let kt = int_div_ceil(config.transfer_length(), config.symbol_size() as u64);
 
     let (kl, ks, zl, zs) = partition(kt, config.source_blocks());
 
     let mut data_index = 0;
     let mut blocks = vec![];
-    if zl > 0 {
-        for _ in 0..zl {
-            let offset = kl as usize * config.symbol_size() as usize;
-            blocks.push((data_index, (data_index + offset)));
-            data_index += offset;
-        }
+    for _ in 0..zl {
+        let offset = kl as u64 * config.symbol_size() as u64;
+        blocks.push((data_index, (data_index + offset)));
+        data_index += offset;
     }
 
-    if zs > 0 {
-        for _ in zl..(zl + zs) {
-            let offset = ks as usize * config.symbol_size() as usize;
-            if data_index + offset > data.len() {
-                // Should only be possible when Kt * T > F. See third to last paragraph in section 4.4.1.2
-                assert!(kt as usize * config.symbol_size() as usize > data.len());
-            }
-            blocks.push((data_index, (data_index + offset)));
-            data_index += offset;
-        }
+    for _ in zl..(zl + zs) {
+        let offset = ks as u64 * config.symbol_size() as u64;
+        blocks.push((data_index, (data_index + offset)));
+        data_index += offset;
     }
 
     blocks
@@ -105,10 +241,45 @@ pub struct Encoder {
 }
 
 impl Encoder {
+    /// Constructs an encoder for the object. With the `parallel` feature, the source blocks are
+    /// encoded concurrently on the rayon thread pool.
     pub fn new(data: &[u8], config: ObjectTransmissionInformation) -> Encoder {
-        let mut block_encoders = vec![];
-        let mut cached_plan: Option<SourceBlockEncodingPlan> = None;
-        for (i, (start, end)) in calculate_block_offsets(data, &config).drain(..).enumerate() {
+        Encoder::with_plan_store(data, config, &mut PlanStore::new())
+    }
+
+    /// Like [`Encoder::new`], but takes encoding plans from `store`. Any plans which had to be
+    /// generated are added to it.
+    pub fn with_plan_store(
+        data: &[u8],
+        config: ObjectTransmissionInformation,
+        store: &mut PlanStore,
+    ) -> Encoder {
+        let block_offsets = calculate_block_offsets(data, &config);
+
+        // Source blocks have at most two distinct sizes, so only generate a plan for each
+        let mut symbol_counts: Vec<u16> = vec![];
+        for (start, end) in block_offsets.iter() {
+            let symbol_count = ((end - start) / config.symbol_size() as usize) as u16;
+            if store.get(symbol_count).is_none() && !symbol_counts.contains(&symbol_count) {
+                symbol_counts.push(symbol_count);
+            }
+        }
+        #[cfg(feature = "parallel")]
+        let plans: Vec<SourceBlockEncodingPlan> = symbol_counts
+            .into_par_iter()
+            .map(SourceBlockEncodingPlan::generate)
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let plans: Vec<SourceBlockEncodingPlan> = symbol_counts
+            .into_iter()
+            .map(SourceBlockEncodingPlan::generate)
+            .collect();
+        for plan in plans {
+            store.insert(plan);
+        }
+
+        let store: &PlanStore = store;
+        let encode_block = |(i, &(start, end)): (usize, &(usize, usize))| {
             // Zero pad if necessary
             let mut padded;
             let block: &[u8] = if end > data.len() {
@@ -119,20 +290,18 @@ impl Encoder {
                 &data[start..end]
             };
 
-            let symbol_count = block.len() / config.symbol_size() as usize;
-            if cached_plan.is_none()
-                || cached_plan.as_ref().unwrap().source_symbol_count != symbol_count as u16
-            {
-                let plan = SourceBlockEncodingPlan::generate(symbol_count as u16);
-                cached_plan = Some(plan);
-            }
-            block_encoders.push(SourceBlockEncoder::with_encoding_plan(
-                i as u8,
-                &config,
-                block,
-                cached_plan.as_ref().unwrap(),
-            ));
-        }
+            let symbol_count = (block.len() / config.symbol_size() as usize) as u16;
+            let plan = store.get(symbol_count).unwrap();
+            SourceBlockEncoder::with_encoding_plan(i as u8, &config, block, plan)
+        };
+        #[cfg(feature = "parallel")]
+        let block_encoders = block_offsets
+            .par_iter()
+            .enumerate()
+            .map(encode_block)
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let block_encoders = block_offsets.iter().enumerate().map(encode_block).collect();
 
         Encoder {
             config,
@@ -165,6 +334,22 @@ impl Encoder {
     pub fn get_block_encoders(&self) -> &Vec<SourceBlockEncoder> {
         &self.blocks
     }
+
+    /// Returns an iterator which lazily generates packets for every source block, interleaved
+    /// in round-robin order: the packet with ESI 0 of each block, then ESI 1, and so on. Source
+    /// packets come first, and repair packets continue until the 24-bit ESI limit is reached.
+    pub fn packet_iter(&self) -> InterleavedPacketIter<'_> {
+        InterleavedPacketIter {
+            blocks: self
+                .blocks
+                .iter()
+                .map(SourceBlockEncoder::repair_iter_state)
+                .collect(),
+            encoders: &self.blocks,
+            encoding_symbol_id: 0,
+            next_block: 0,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -176,31 +361,122 @@ pub struct SourceBlockEncodingPlan {
 
 impl SourceBlockEncodingPlan {
     // Generates an encoding plan that is valid for any combination of data length and symbol size
-    // where ceil(data_length / symbol_size) = symbol_count
+    // where ceil(data_length / symbol_size) has the same extended symbol count, K', as symbol_count
     pub fn generate(symbol_count: u16) -> SourceBlockEncodingPlan {
         // TODO: refactor pi_solver, so that we don't need this dummy data to generate a plan
-        let symbols = vec![Symbol::new(vec![0]); symbol_count as usize];
+        let symbols = vec![0; symbol_count as usize];
         let (_, ops) = gen_intermediate_symbols(&symbols, 1, SPARSE_MATRIX_THRESHOLD);
         SourceBlockEncodingPlan {
             operations: ops.unwrap(),
             source_symbol_count: symbol_count,
         }
     }
+    pub(crate) fn from_operations(
+        source_symbol_count: u16,
+        operations: Vec<SymbolOps>,
+    ) -> SourceBlockEncodingPlan {
+        SourceBlockEncodingPlan {
+            operations,
+            source_symbol_count,
+        }
+    }
+
+    pub fn source_symbol_count(&self) -> u16 {
+        self.source_symbol_count
+    }
+
+    pub(crate) fn operations(&self) -> &[SymbolOps] {
+        &self.operations
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde_support",
+    serde(
+        try_from = "SerializedSourceBlockEncoder",
+        into = "SerializedSourceBlockEncoder"
+    )
+)]
 pub struct SourceBlockEncoder {
+    source_block_id: u8,
+    symbol_size: u16,
+    // Source symbols stored back to back in ESI order, after sub-block interleaving
+    source_symbols: Vec<u8>,
+    intermediate_symbols: Vec<Symbol>,
+}
+
+// Serialized form of SourceBlockEncoder, which stores each source symbol separately, as earlier
+// versions did
+#[cfg(feature = "serde_support")]
+#[derive(Serialize, Deserialize)]
+struct SerializedSourceBlockEncoder {
     source_block_id: u8,
     source_symbols: Vec<Symbol>,
     intermediate_symbols: Vec<Symbol>,
 }
 
+#[cfg(feature = "serde_support")]
+impl From<SourceBlockEncoder> for SerializedSourceBlockEncoder {
+    fn from(encoder: SourceBlockEncoder) -> Self {
+        SerializedSourceBlockEncoder {
+            source_block_id: encoder.source_block_id,
+            source_symbols: encoder
+                .source_symbols
+                .chunks_exact(encoder.symbol_size as usize)
+                .map(|symbol| Symbol::new(symbol.to_vec()))
+                .collect(),
+            intermediate_symbols: encoder.intermediate_symbols,
+        }
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl TryFrom<SerializedSourceBlockEncoder> for SourceBlockEncoder {
+    type Error = &'static str;
+
+    fn try_from(serialized: SerializedSourceBlockEncoder) -> Result<Self, Self::Error> {
+        let source_symbol_count = serialized.source_symbols.len();
+        if source_symbol_count == 0 || source_symbol_count > MAX_SOURCE_SYMBOLS_PER_BLOCK as usize {
+            return Err("invalid number of source symbols");
+        }
+        if serialized.intermediate_symbols.len()
+            != num_intermediate_symbols(source_symbol_count as u32) as usize
+        {
+            return Err("invalid number of intermediate symbols");
+        }
+        let symbol_size = serialized.source_symbols[0].as_bytes().len();
+        if symbol_size == 0
+            || symbol_size > u16::MAX as usize
+            || serialized
+                .source_symbols
+                .iter()
+                .chain(serialized.intermediate_symbols.iter())
+                .any(|symbol| symbol.as_bytes().len() != symbol_size)
+        {
+            return Err("symbols must all have the same, non-zero size");
+        }
+        Ok(SourceBlockEncoder {
+            source_block_id: serialized.source_block_id,
+            symbol_size: symbol_size as u16,
+            source_symbols: serialized
+                .source_symbols
+                .into_iter()
+                .flat_map(Symbol::into_bytes)
+                .collect(),
+            intermediate_symbols: serialized.intermediate_symbols,
+        })
+    }
+}
+
 impl SourceBlockEncoder {
-    fn create_symbols(config: &ObjectTransmissionInformation, data: &[u8]) -> Vec<Symbol> {
+    // Copies the block into a single buffer of source symbols
+    fn create_symbols(config: &ObjectTransmissionInformation, data: &[u8]) -> Vec<u8> {
         assert_eq!(data.len() % config.symbol_size() as usize, 0);
         if config.sub_blocks() > 1 {
-            let mut symbols = vec![vec![]; data.len() / config.symbol_size() as usize];
+            let symbol_size = config.symbol_size() as usize;
+            let mut symbols = vec![0; data.len()];
             let (tl, ts, nl, ns) = partition(
                 (config.symbol_size() / config.symbol_alignment() as u16) as u32,
                 config.sub_blocks(),
@@ -208,26 +484,29 @@ impl SourceBlockEncoder {
             // Divide the block into sub-blocks and then concatenate the sub-symbols into symbols
             // See second to last paragraph in section 4.4.1.2.
             let mut offset = 0;
+            let mut symbol_offset = 0;
             for sub_block in 0..(nl + ns) {
                 let bytes = if sub_block < nl {
                     tl as usize * config.symbol_alignment() as usize
                 } else {
                     ts as usize * config.symbol_alignment() as usize
                 };
-                for symbol in &mut symbols {
-                    symbol.extend_from_slice(&data[offset..offset + bytes]);
+                for symbol in symbols.chunks_exact_mut(symbol_size) {
+                    symbol[symbol_offset..symbol_offset + bytes]
+                        .copy_from_slice(&data[offset..offset + bytes]);
                     offset += bytes;
                 }
+                symbol_offset += bytes;
             }
             assert_eq!(offset, data.len());
-            symbols.drain(..).map(Symbol::new).collect()
+            symbols
         } else {
-            data.chunks(config.symbol_size() as usize)
-                .map(|x| Symbol::new(Vec::from(x)))
-                .collect()
+            Vec::from(data)
         }
     }
 
+    /// Constructs an encoder for one source block. `data` is borrowed, and copied exactly once
+    /// into a single buffer of source symbols, which packets are later written from.
     pub fn new(
         source_block_id: u8,
         config: &ObjectTransmissionInformation,
@@ -243,6 +522,7 @@ impl SourceBlockEncoder {
 
         SourceBlockEncoder {
             source_block_id,
+            symbol_size: config.symbol_size(),
             source_symbols,
             intermediate_symbols: intermediate_symbols.unwrap(),
         }
@@ -255,8 +535,13 @@ impl SourceBlockEncoder {
         plan: &SourceBlockEncodingPlan,
     ) -> SourceBlockEncoder {
         let source_symbols = SourceBlockEncoder::create_symbols(config, data);
-        // TODO: this could be more lenient and support anything with the same extended symbol count
-        assert_eq!(source_symbols.len(), plan.source_symbol_count as usize);
+        // The operations only depend on the extended symbol count
+        assert_eq!(
+            extended_source_block_symbols(
+                (source_symbols.len() / config.symbol_size() as usize) as u32
+            ),
+            extended_source_block_symbols(plan.source_symbol_count as u32)
+        );
 
         let intermediate_symbols = gen_intermediate_symbols_with_plan(
             &source_symbols,
@@ -266,42 +551,71 @@ impl SourceBlockEncoder {
 
         SourceBlockEncoder {
             source_block_id,
+            symbol_size: config.symbol_size(),
             source_symbols,
             intermediate_symbols,
         }
     }
 
+    fn source_symbol_count(&self) -> u32 {
+        (self.source_symbols.len() / self.symbol_size as usize) as u32
+    }
+
+    /// Length in bytes of each serialized packet, as written by `source_packets_into()` and
+    /// `repair_packets_into()`
+    pub fn packet_size(&self) -> usize {
+        PAYLOAD_ID_SIZE + self.symbol_size as usize
+    }
+
     pub fn source_packets(&self) -> Vec<EncodingPacket> {
-        let mut esi: i32 = -1;
         self.source_symbols
-            .iter()
-            .map(|symbol| {
-                esi += 1;
+            .chunks_exact(self.symbol_size as usize)
+            .enumerate()
+            .map(|(esi, symbol)| {
                 EncodingPacket::new(
                     PayloadId::new(self.source_block_id, esi as u32),
-                    symbol.as_bytes().to_vec(),
+                    symbol.to_vec(),
                 )
             })
             .collect()
     }
 
+    /// Serializes source packets, starting at `start_encoding_symbol_id`, directly into
+    /// `buffer`. Packets are written back to back, each `packet_size()` bytes long, until the
+    /// buffer is full or no source packets remain. Returns the number of packets written.
+    pub fn source_packets_into(&self, start_encoding_symbol_id: u32, buffer: &mut [u8]) -> usize {
+        let symbols = self
+            .source_symbols
+            .chunks_exact(self.symbol_size as usize)
+            .skip(start_encoding_symbol_id as usize);
+        let mut written = 0;
+        for (packet, symbol) in buffer.chunks_exact_mut(self.packet_size()).zip(symbols) {
+            let esi = start_encoding_symbol_id + written as u32;
+            let (payload_id, payload) = packet.split_at_mut(PAYLOAD_ID_SIZE);
+            payload_id.copy_from_slice(&PayloadId::new(self.source_block_id, esi).serialize());
+            payload.copy_from_slice(symbol);
+            written += 1;
+        }
+        written
+    }
+
     // See section 5.3.4
     pub fn repair_packets(&self, start_repair_symbol_id: u32, packets: u32) -> Vec<EncodingPacket> {
-        let start_encoding_symbol_id = start_repair_symbol_id
-            + extended_source_block_symbols(self.source_symbols.len() as u32);
+        let start_encoding_symbol_id =
+            start_repair_symbol_id + extended_source_block_symbols(self.source_symbol_count());
         let mut result = vec![];
-        let lt_symbols = num_lt_symbols(self.source_symbols.len() as u32);
-        let sys_index = systematic_index(self.source_symbols.len() as u32);
-        let p1 = calculate_p1(self.source_symbols.len() as u32);
+        let lt_symbols = num_lt_symbols(self.source_symbol_count());
+        let sys_index = systematic_index(self.source_symbol_count());
+        let p1 = calculate_p1(self.source_symbol_count());
         for i in 0..packets {
             let tuple = intermediate_tuple(start_encoding_symbol_id + i, lt_symbols, sys_index, p1);
             result.push(EncodingPacket::new(
                 PayloadId::new(
                     self.source_block_id,
-                    self.source_symbols.len() as u32 + start_repair_symbol_id + i,
+                    self.source_symbol_count() + start_repair_symbol_id + i,
                 ),
                 enc(
-                    self.source_symbols.len() as u32,
+                    self.source_symbol_count(),
                     &self.intermediate_symbols,
                     tuple,
                 )
@@ -310,27 +624,172 @@ impl SourceBlockEncoder {
         }
         result
     }
+
+    /// Returns an iterator which lazily generates repair packets, starting at
+    /// `start_repair_symbol_id`, until the 24-bit ESI limit is reached.
+    pub fn repair_iter(&self, start_repair_symbol_id: u32) -> RepairPacketIter<'_> {
+        RepairPacketIter {
+            encoder: self,
+            state: self.repair_iter_state(),
+            next_encoding_symbol_id: self
+                .source_symbol_count()
+                .saturating_add(start_repair_symbol_id),
+        }
+    }
+
+    fn repair_iter_state(&self) -> RepairState {
+        let source_symbols = self.source_symbol_count();
+        RepairState {
+            // Offset from ESI to ISI. See section 5.3.1
+            padding_symbols: extended_source_block_symbols(source_symbols) - source_symbols,
+            lt_symbols: num_lt_symbols(source_symbols),
+            sys_index: systematic_index(source_symbols),
+            p1: calculate_p1(source_symbols),
+        }
+    }
+
+    // Generates the packet with the given ESI, which may be a source or repair symbol
+    fn packet(&self, state: &RepairState, encoding_symbol_id: u32) -> EncodingPacket {
+        let source_symbols = self.source_symbol_count();
+        let data = if encoding_symbol_id < source_symbols {
+            let offset = encoding_symbol_id as usize * self.symbol_size as usize;
+            self.source_symbols[offset..offset + self.symbol_size as usize].to_vec()
+        } else {
+            let tuple = intermediate_tuple(
+                encoding_symbol_id + state.padding_symbols,
+                state.lt_symbols,
+                state.sys_index,
+                state.p1,
+            );
+            enc(source_symbols, &self.intermediate_symbols, tuple).into_bytes()
+        };
+        EncodingPacket::new(
+            PayloadId::new(self.source_block_id, encoding_symbol_id),
+            data,
+        )
+    }
+
+    /// Serializes repair packets, starting at `start_repair_symbol_id`, directly into `buffer`.
+    /// Packets are written back to back, each `packet_size()` bytes long, until the buffer is
+    /// full or the 24-bit ESI limit is reached. Returns the number of packets written.
+    pub fn repair_packets_into(&self, start_repair_symbol_id: u32, buffer: &mut [u8]) -> usize {
+        let source_symbols = self.source_symbol_count();
+        let state = self.repair_iter_state();
+        let start_encoding_symbol_id = source_symbols.saturating_add(start_repair_symbol_id);
+        let remaining = (MAX_ENCODING_SYMBOL_ID + 1).saturating_sub(start_encoding_symbol_id);
+        let mut written = 0;
+        for packet in buffer
+            .chunks_exact_mut(self.packet_size())
+            .take(remaining as usize)
+        {
+            let encoding_symbol_id = start_encoding_symbol_id + written as u32;
+            let tuple = intermediate_tuple(
+                encoding_symbol_id + state.padding_symbols,
+                state.lt_symbols,
+                state.sys_index,
+                state.p1,
+            );
+            let (payload_id, payload) = packet.split_at_mut(PAYLOAD_ID_SIZE);
+            payload_id.copy_from_slice(
+                &PayloadId::new(self.source_block_id, encoding_symbol_id).serialize(),
+            );
+            enc_into(source_symbols, &self.intermediate_symbols, tuple, payload);
+            written += 1;
+        }
+        written
+    }
+}
+
+// Largest ESI that fits in a PayloadId. See section 3.2
+pub(crate) const MAX_ENCODING_SYMBOL_ID: u32 = (1 << 24) - 1;
+
+// Parameters for generating the repair symbols of a block
+#[derive(Clone, Debug)]
+struct RepairState {
+    padding_symbols: u32,
+    lt_symbols: u32,
+    sys_index: u32,
+    p1: u32,
+}
+
+/// Lazily generates the repair packets of a [`SourceBlockEncoder`]. See
+/// [`SourceBlockEncoder::repair_iter`].
+#[derive(Clone, Debug)]
+pub struct RepairPacketIter<'a> {
+    encoder: &'a SourceBlockEncoder,
+    state: RepairState,
+    next_encoding_symbol_id: u32,
+}
+
+impl Iterator for RepairPacketIter<'_> {
+    type Item = EncodingPacket;
+
+    fn next(&mut self) -> Option<EncodingPacket> {
+        if self.next_encoding_symbol_id > MAX_ENCODING_SYMBOL_ID {
+            return None;
+        }
+        let packet = self
+            .encoder
+            .packet(&self.state, self.next_encoding_symbol_id);
+        self.next_encoding_symbol_id += 1;
+        Some(packet)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (MAX_ENCODING_SYMBOL_ID + 1).saturating_sub(self.next_encoding_symbol_id);
+        (remaining as usize, Some(remaining as usize))
+    }
+}
+
+impl ExactSizeIterator for RepairPacketIter<'_> {}
+
+/// Lazily generates the packets of every block of an [`Encoder`], interleaving the blocks. See
+/// [`Encoder::packet_iter`].
+#[derive(Clone, Debug)]
+pub struct InterleavedPacketIter<'a> {
+    encoders: &'a [SourceBlockEncoder],
+    blocks: Vec<RepairState>,
+    encoding_symbol_id: u32,
+    next_block: usize,
+}
+
+impl Iterator for InterleavedPacketIter<'_> {
+    type Item = EncodingPacket;
+
+    fn next(&mut self) -> Option<EncodingPacket> {
+        if self.next_block == self.encoders.len() {
+            self.next_block = 0;
+            self.encoding_symbol_id += 1;
+        }
+        if self.encoding_symbol_id > MAX_ENCODING_SYMBOL_ID || self.encoders.is_empty() {
+            return None;
+        }
+        let block = self.next_block;
+        self.next_block += 1;
+        Some(self.encoders[block].packet(&self.blocks[block], self.encoding_symbol_id))
+    }
 }
 
 #[allow(non_snake_case)]
 fn create_d(
-    source_block: &[Symbol],
+    source_block: &[u8],
     symbol_size: usize,
     extended_source_symbols: usize,
 ) -> Vec<Symbol> {
-    let L = num_intermediate_symbols(source_block.len() as u32);
-    let S = num_ldpc_symbols(source_block.len() as u32);
-    let H = num_hdpc_symbols(source_block.len() as u32);
+    let source_symbols = source_block.len() / symbol_size;
+    let L = num_intermediate_symbols(source_symbols as u32);
+    let S = num_ldpc_symbols(source_symbols as u32);
+    let H = num_hdpc_symbols(source_symbols as u32);
 
     let mut D = Vec::with_capacity(L as usize);
     for _ in 0..(S + H) {
         D.push(Symbol::zero(symbol_size));
     }
-    for symbol in source_block {
-        D.push(symbol.clone());
+    for symbol in source_block.chunks_exact(symbol_size) {
+        D.push(Symbol::new(Vec::from(symbol)));
     }
     // Extend the source block with padding. See section 5.3.2
-    for _ in 0..(extended_source_symbols - source_block.len()) {
+    for _ in 0..(extended_source_symbols - source_symbols) {
         D.push(Symbol::zero(symbol_size));
     }
     assert_eq!(D.len(), L as usize);
@@ -340,11 +799,12 @@ fn create_d(
 // See section 5.3.3.4
 #[allow(non_snake_case)]
 fn gen_intermediate_symbols(
-    source_block: &[Symbol],
+    source_block: &[u8],
     symbol_size: usize,
     sparse_threshold: u32,
 ) -> (Option<Vec<Symbol>>, Option<Vec<SymbolOps>>) {
-    let extended_source_symbols = extended_source_block_symbols(source_block.len() as u32);
+    let extended_source_symbols =
+        extended_source_block_symbols((source_block.len() / symbol_size) as u32);
     let D = create_d(source_block, symbol_size, extended_source_symbols as usize);
 
     let indices: Vec<u32> = (0..extended_source_symbols).collect();
@@ -361,26 +821,42 @@ fn gen_intermediate_symbols(
 
 #[allow(non_snake_case)]
 fn gen_intermediate_symbols_with_plan(
-    source_block: &[Symbol],
+    source_block: &[u8],
     symbol_size: usize,
     operation_vector: &[SymbolOps],
 ) -> Vec<Symbol> {
-    let extended_source_symbols = extended_source_block_symbols(source_block.len() as u32);
+    let extended_source_symbols =
+        extended_source_block_symbols((source_block.len() / symbol_size) as u32);
     let mut D = create_d(source_block, symbol_size, extended_source_symbols as usize);
 
-    for op in operation_vector {
-        perform_op(op, &mut D);
-    }
+    perform_ops(operation_vector, &mut D);
     D
 }
 
 // Enc[] function, as defined in section 5.3.5.3
-#[allow(clippy::many_single_char_names)]
 fn enc(
     source_block_symbols: u32,
     intermediate_symbols: &[Symbol],
     source_tuple: (u32, u32, u32, u32, u32, u32),
 ) -> Symbol {
+    let mut result = vec![0; intermediate_symbols[0].as_bytes().len()];
+    enc_into(
+        source_block_symbols,
+        intermediate_symbols,
+        source_tuple,
+        &mut result,
+    );
+    Symbol::new(result)
+}
+
+// Enc[] function, writing the encoded symbol into `result`
+#[allow(clippy::many_single_char_names)]
+fn enc_into(
+    source_block_symbols: u32,
+    intermediate_symbols: &[Symbol],
+    source_tuple: (u32, u32, u32, u32, u32, u32),
+    result: &mut [u8],
+) {
     let w = num_lt_symbols(source_block_symbols);
     let p = num_pi_symbols(source_block_symbols);
     let p1 = calculate_p1(source_block_symbols);
@@ -392,27 +868,25 @@ fn enc(
     assert!(1 <= a1 && a < w);
     assert!(b1 < w);
 
-    let mut result = intermediate_symbols[b as usize].clone();
+    result.copy_from_slice(intermediate_symbols[b as usize].as_bytes());
     for _ in 1..d {
         b = (b + a) % w;
-        result += &intermediate_symbols[b as usize];
+        add_assign(result, intermediate_symbols[b as usize].as_bytes());
     }
 
     while b1 >= p {
         b1 = (b1 + a1) % p1;
     }
 
-    result += &intermediate_symbols[(w + b1) as usize];
+    add_assign(result, intermediate_symbols[(w + b1) as usize].as_bytes());
 
     for _ in 1..d1 {
         b1 = (b1 + a1) % p1;
         while b1 >= p {
             b1 = (b1 + a1) % p1;
         }
-        result += &intermediate_symbols[(w + b1) as usize];
+        add_assign(result, intermediate_symbols[(w + b1) as usize].as_bytes());
     }
-
-    result
 }
 
 #[cfg(feature = "std")]
@@ -447,13 +921,8 @@ mod tests {
         data
     }
 
-    fn gen_test_symbols() -> Vec<Symbol> {
-        let mut source_block: Vec<Symbol> = vec![];
-        for _ in 0..NUM_SYMBOLS {
-            let data = gen_test_data(SYMBOL_SIZE);
-            source_block.push(Symbol::new(data));
-        }
-        source_block
+    fn gen_test_symbols() -> Vec<u8> {
+        gen_test_data(NUM_SYMBOLS as usize * SYMBOL_SIZE)
     }
 
     #[test]
@@ -477,10 +946,10 @@ mod tests {
         let sys_index = systematic_index(NUM_SYMBOLS);
         let p1 = calculate_p1(NUM_SYMBOLS);
         // See section 5.3.3.4.1, item 1.
-        for (i, source_symbol) in source_symbols.iter().enumerate() {
+        for (i, source_symbol) in source_symbols.chunks_exact(SYMBOL_SIZE).enumerate() {
             let tuple = intermediate_tuple(i as u32, lt_symbols, sys_index, p1);
             let encoded = enc(NUM_SYMBOLS, &intermediate_symbols, tuple);
-            assert_eq!(source_symbol, &encoded);
+            assert_eq!(source_symbol, encoded.as_bytes());
         }
     }
 
@@ -561,6 +1030,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn packets_into_buffer() {
+        let symbol_size = 16;
+        let data = gen_test_data(symbol_size * 10);
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 1, 2, 8);
+        let encoder = SourceBlockEncoder::new(3, &config, &data);
+        let packet_size = encoder.packet_size();
+        assert_eq!(packet_size, symbol_size + 4);
+
+        // Room for 4 packets, plus some slack which must be left untouched
+        let mut buffer = vec![0xFF; packet_size * 4 + 7];
+        assert_eq!(encoder.source_packets_into(8, &mut buffer), 2);
+        let expected: Vec<u8> = encoder.source_packets()[8..]
+            .iter()
+            .flat_map(|packet| packet.serialize())
+            .collect();
+        assert_eq!(buffer[..packet_size * 2], expected[..]);
+
+        assert_eq!(encoder.repair_packets_into(5, &mut buffer), 4);
+        let expected: Vec<u8> = encoder
+            .repair_packets(5, 4)
+            .iter()
+            .flat_map(|packet| packet.serialize())
+            .collect();
+        assert_eq!(buffer[..packet_size * 4], expected[..]);
+        assert!(buffer[packet_size * 4..].iter().all(|&byte| byte == 0xFF));
+    }
+
+    #[test]
+    fn repair_packets_into_buffer_near_esi_limit() {
+        let symbol_size = 8;
+        let data = gen_test_data(symbol_size * 10);
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 1, 1, 8);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+        let packet_size = encoder.packet_size();
+
+        // Only the last two ESIs fit in 24 bits
+        let start_repair_symbol_id = 16777215 - 10 - 1;
+        let mut buffer = vec![0xFF; packet_size * 4];
+        assert_eq!(
+            encoder.repair_packets_into(start_repair_symbol_id, &mut buffer),
+            2
+        );
+        let expected: Vec<u8> = encoder
+            .repair_iter(start_repair_symbol_id)
+            .flat_map(|packet| packet.serialize())
+            .collect();
+        assert_eq!(buffer[..packet_size * 2], expected[..]);
+        assert!(buffer[packet_size * 2..].iter().all(|&byte| byte == 0xFF));
+
+        assert_eq!(
+            encoder.repair_packets_into(16777215 - 10 + 1, &mut buffer),
+            0
+        );
+        assert_eq!(encoder.repair_packets_into(u32::MAX, &mut buffer), 0);
+    }
+
+    #[test]
+    fn repair_iter() {
+        let symbol_size = 8;
+        let data = gen_test_data(symbol_size * 10);
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 1, 1, 8);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+
+        let packets: Vec<EncodingPacket> = encoder.repair_iter(3).take(20).collect();
+        assert_eq!(packets, encoder.repair_packets(3, 20));
+
+        // Stops at the largest ESI that fits in 24 bits
+        let mut iter = encoder.repair_iter(16777215 - 10 - 1);
+        assert_eq!(iter.len(), 2);
+        assert_eq!(
+            iter.nth(1).unwrap().payload_id().encoding_symbol_id(),
+            16777215
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn interleaved_packet_iter() {
+        let data = gen_test_data(1000);
+        let config = ObjectTransmissionInformation::new(1000, 8, 3, 1, 8);
+        let encoder = Encoder::new(&data, config);
+        let blocks = encoder.get_block_encoders();
+        assert_eq!(blocks.len(), 3);
+
+        let packets: Vec<EncodingPacket> = encoder.packet_iter().take(3 * 50).collect();
+        for (i, packet) in packets.iter().enumerate() {
+            let block = &blocks[i % 3];
+            let esi = (i / 3) as u32;
+            assert_eq!(packet.payload_id().source_block_number(), (i % 3) as u8);
+            assert_eq!(packet.payload_id().encoding_symbol_id(), esi);
+            let source_packets = block.source_packets();
+            let expected = if (esi as usize) < source_packets.len() {
+                source_packets[esi as usize].clone()
+            } else {
+                block
+                    .repair_packets(esi - source_packets.len() as u32, 1)
+                    .pop()
+                    .unwrap()
+            };
+            assert_eq!(packet, &expected);
+        }
+    }
+
     #[cfg(not(feature = "python"))]
     #[test]
     fn test_builder() {
@@ -571,6 +1145,99 @@ mod tests {
         assert_eq!(builder.build(&data), encoder);
     }
 
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn builder_parameters() {
+        let mut builder = EncoderBuilder::new();
+        builder.set_max_packet_size(1283);
+        builder.set_repair_overhead(10);
+        let parameters = builder.try_parameters(1_000_000).unwrap();
+        assert_eq!(
+            parameters.config(),
+            ObjectTransmissionInformation::new(1_000_000, 1280, 1, 1, 8)
+        );
+        assert_eq!(parameters.source_symbols_per_block(), &[782]);
+        assert_eq!(parameters.sub_symbol_sizes(), &[1280]);
+        assert_eq!(parameters.repair_packets_per_block(), 79);
+
+        // Limiting the decoder's working memory requires sub-blocking, and then multiple blocks
+        builder.set_decoder_memory_requirement(256 * 1024);
+        let parameters = builder.try_parameters(1_000_000).unwrap();
+        assert_eq!(
+            parameters.config(),
+            ObjectTransmissionInformation::new(1_000_000, 1280, 1, 4, 8)
+        );
+        assert_eq!(parameters.sub_symbol_sizes(), &[320, 320, 320, 320]);
+        let parameters = builder.try_parameters(10_000_000).unwrap();
+        assert_eq!(parameters.config().source_blocks(), 2);
+        assert_eq!(parameters.config().sub_blocks(), 20);
+        assert_eq!(parameters.source_symbols_per_block(), &[3907, 3906]);
+        assert_eq!(parameters.repair_packets_per_block(), 391);
+
+        builder.set_alignment(4);
+        builder.set_max_packet_size(250);
+        let parameters = builder.try_parameters(1_000).unwrap();
+        assert_eq!(
+            parameters.config(),
+            ObjectTransmissionInformation::new(1_000, 248, 1, 1, 4)
+        );
+        let data = gen_test_data(1_000);
+        assert_eq!(
+            builder.build(&data),
+            Encoder::new(&data, parameters.config())
+        );
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn unsatisfiable_parameters() {
+        let mut builder = EncoderBuilder::new();
+        builder.set_max_source_blocks(2);
+        assert_eq!(
+            builder.try_parameters(200_000_000),
+            Err(RaptorQError::TooManySourceBlocks {
+                required: 4,
+                limit: 2
+            })
+        );
+
+        let mut builder = EncoderBuilder::new();
+        builder.set_decoder_memory_requirement(100);
+        assert_eq!(
+            builder.try_parameters(1_000),
+            Err(RaptorQError::InvalidEncodingParameters(
+                "decoder memory requirement is too small for the smallest source block"
+            ))
+        );
+
+        let mut builder = EncoderBuilder::new();
+        builder.set_alignment(0);
+        assert_eq!(
+            builder.try_parameters(1_000),
+            Err(RaptorQError::InvalidSymbolAlignment {
+                symbol_size: 1024,
+                alignment: 0
+            })
+        );
+        builder.set_alignment(16);
+        builder.set_max_packet_size(8);
+        assert_eq!(
+            builder.try_parameters(1_000),
+            Err(RaptorQError::InvalidEncodingParameters(
+                "maximum packet size is smaller than the symbol alignment"
+            ))
+        );
+
+        let mut builder = EncoderBuilder::new();
+        builder.set_repair_overhead(u32::MAX);
+        assert_eq!(
+            builder.try_parameters(1_000_000),
+            Err(RaptorQError::InvalidEncodingParameters(
+                "repair overhead exceeds the encoding symbol ID space"
+            ))
+        );
+    }
+
     #[cfg(not(feature = "python"))]
     #[test]
     fn padding_constraint_exact() {
@@ -623,4 +1290,41 @@ mod tests {
         }
         assert_eq!(ids.len(), encoder.get_block_encoders().len());
     }
+
+    #[cfg(feature = "serde_support")]
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn serialized_source_block_encoder() {
+        let config = ObjectTransmissionInformation::new(0, SYMBOL_SIZE as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(1, &config, &gen_test_symbols());
+        let serialized = SerializedSourceBlockEncoder::from(encoder.clone());
+        assert_eq!(serialized.source_symbols.len(), NUM_SYMBOLS as usize);
+        assert_eq!(
+            SourceBlockEncoder::try_from(serialized),
+            Ok(encoder.clone())
+        );
+
+        let mut mismatched = SerializedSourceBlockEncoder::from(encoder.clone());
+        mismatched.source_symbols[3] = Symbol::zero(SYMBOL_SIZE + 1);
+        assert!(SourceBlockEncoder::try_from(mismatched).is_err());
+
+        let mut missing = SerializedSourceBlockEncoder::from(encoder);
+        missing.intermediate_symbols.pop();
+        assert!(SourceBlockEncoder::try_from(missing).is_err());
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn deserialize_baseline_encoder_builder() {
+        let mut builder = EncoderBuilder::new();
+        builder.set_max_packet_size(1280);
+        let mut baseline = serde_json::to_value(&builder).unwrap();
+        let fields = baseline.as_object_mut().unwrap();
+        // Drop the fields which earlier versions did not serialize
+        for field in ["alignment", "repair_overhead_percent", "max_source_blocks"] {
+            assert!(fields.remove(field).is_some(), "{field}");
+        }
+        let restored: EncoderBuilder = serde_json::from_value(baseline).unwrap();
+        assert_eq!(restored, builder);
+    }
 }
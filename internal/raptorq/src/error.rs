@@ -0,0 +1,159 @@
+#[cfg(not(feature = "std"))]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fmt;
+
+use crate::backend::Backend;
+
+/// Errors returned by the fallible (`try_*`) APIs, when validating parameters or packets
+/// which may have come from an untrusted source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RaptorQError {
+    /// Serialized input was shorter than the minimum required length
+    InsufficientData { expected: usize, actual: usize },
+    /// Encoding Symbol ID does not fit in 24 bits. See section 3.2
+    InvalidEncodingSymbolId(u32),
+    /// Transfer length exceeds the maximum supported by RFC 6330
+    TransferLengthTooLarge(u64),
+    /// Symbol size is zero
+    InvalidSymbolSize(u16),
+    /// Symbol alignment is zero, or the symbol size is not a multiple of it
+    InvalidSymbolAlignment { symbol_size: u16, alignment: u8 },
+    /// Reserved field of the Common FEC OTI is not zero, or the FEC Encoding ID written there by
+    /// older versions of the JavaScript wrapper. See section 3.3.2
+    InvalidReservedField(u8),
+    /// FEC Encoding ID is not the one assigned to RaptorQ. See section 3.3.1
+    UnsupportedFecEncodingId(u8),
+    /// Number of source blocks is zero
+    InvalidSourceBlockCount(u8),
+    /// Number of sub-blocks is zero
+    InvalidSubBlockCount(u16),
+    /// A source block would contain more than K'_max source symbols. See section 4.4.1.2
+    TooManySourceSymbols(u64),
+    /// Object would need more source blocks than the limit given to
+    /// [`EncoderBuilder`](crate::EncoderBuilder)
+    TooManySourceBlocks { required: u32, limit: u8 },
+    /// Encoding parameters cannot be derived from the constraints given to
+    /// [`EncoderBuilder`](crate::EncoderBuilder). See section 4.3
+    InvalidEncodingParameters(&'static str),
+    /// Packet's source block number is not part of the object
+    InvalidSourceBlockNumber {
+        source_block_number: u8,
+        source_blocks: u8,
+    },
+    /// Packet was given to the decoder of a different source block
+    SourceBlockMismatch { expected: u8, actual: u8 },
+    /// Packet's payload length does not match the symbol size
+    SymbolSizeMismatch { expected: usize, actual: usize },
+    /// Packet's checksum does not match its contents
+    ChecksumMismatch,
+    /// Packet was authenticated with a key which is not known to the
+    /// [`PacketAuthenticator`](crate::PacketAuthenticator)
+    UnknownKeyId(u8),
+    /// Packet's authentication tag does not match its contents, or the object it was sent for
+    AuthenticationFailed,
+    /// Packet cannot be sealed, since the [`PacketAuthenticator`](crate::PacketAuthenticator) has
+    /// no signing key
+    NoSigningKey,
+    /// Serialized encoding plan or plan store is malformed
+    InvalidPlan(&'static str),
+    /// Source block already retains the maximum number of symbols allowed by the decoder's
+    /// configuration
+    RetainedSymbolLimit { source_block_number: u8, limit: u32 },
+    /// Packet would start decoding another source block, while the maximum number allowed by the
+    /// decoder's configuration are already active
+    ActiveBlockLimit { source_block_number: u8, limit: u32 },
+    /// Serialized decoder checkpoint is malformed, or cannot be resumed
+    InvalidCheckpoint(&'static str),
+    /// Backend cannot be used on this machine
+    UnsupportedBackend(Backend),
+}
+
+impl fmt::Display for RaptorQError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaptorQError::InsufficientData { expected, actual } => {
+                write!(f, "expected at least {expected} bytes, but got {actual}")
+            }
+            RaptorQError::InvalidEncodingSymbolId(esi) => {
+                write!(f, "encoding symbol id {esi} does not fit in 24 bits")
+            }
+            RaptorQError::TransferLengthTooLarge(length) => {
+                write!(f, "transfer length {length} is too large")
+            }
+            RaptorQError::InvalidSymbolSize(size) => write!(f, "invalid symbol size {size}"),
+            RaptorQError::InvalidSymbolAlignment {
+                symbol_size,
+                alignment,
+            } => write!(
+                f,
+                "symbol size {symbol_size} is not a multiple of alignment {alignment}"
+            ),
+            RaptorQError::InvalidReservedField(value) => {
+                write!(f, "reserved OTI field must be zero, but was {value}")
+            }
+            RaptorQError::UnsupportedFecEncodingId(id) => write!(
+                f,
+                "unsupported FEC Encoding ID {id}, expected {}",
+                crate::base::FEC_ENCODING_ID
+            ),
+            RaptorQError::InvalidSourceBlockCount(count) => {
+                write!(f, "invalid number of source blocks {count}")
+            }
+            RaptorQError::InvalidSubBlockCount(count) => {
+                write!(f, "invalid number of sub-blocks {count}")
+            }
+            RaptorQError::TooManySourceSymbols(symbols) => {
+                write!(f, "{symbols} source symbols per block exceeds the maximum")
+            }
+            RaptorQError::TooManySourceBlocks { required, limit } => write!(
+                f,
+                "{required} source blocks are required, but the limit is {limit}"
+            ),
+            RaptorQError::InvalidEncodingParameters(reason) => {
+                write!(f, "cannot derive encoding parameters: {reason}")
+            }
+            RaptorQError::InvalidSourceBlockNumber {
+                source_block_number,
+                source_blocks,
+            } => write!(
+                f,
+                "source block number {source_block_number} is out of range for {source_blocks} blocks"
+            ),
+            RaptorQError::SourceBlockMismatch { expected, actual } => write!(
+                f,
+                "packet for source block {actual} given to decoder for block {expected}"
+            ),
+            RaptorQError::SymbolSizeMismatch { expected, actual } => {
+                write!(f, "expected symbol of {expected} bytes, but got {actual}")
+            }
+            RaptorQError::ChecksumMismatch => write!(f, "packet checksum does not match"),
+            RaptorQError::UnknownKeyId(key_id) => write!(f, "unknown authentication key {key_id}"),
+            RaptorQError::AuthenticationFailed => write!(f, "packet authentication failed"),
+            RaptorQError::NoSigningKey => write!(f, "no signing key has been set"),
+            RaptorQError::InvalidPlan(reason) => write!(f, "invalid encoding plan: {reason}"),
+            RaptorQError::RetainedSymbolLimit {
+                source_block_number,
+                limit,
+            } => write!(
+                f,
+                "source block {source_block_number} already retains the limit of {limit} symbols"
+            ),
+            RaptorQError::ActiveBlockLimit {
+                source_block_number,
+                limit,
+            } => write!(
+                f,
+                "cannot start decoding source block {source_block_number}, since the limit of {limit} blocks are active"
+            ),
+            RaptorQError::InvalidCheckpoint(reason) => write!(f, "invalid checkpoint: {reason}"),
+            RaptorQError::UnsupportedBackend(backend) => {
+                write!(f, "{backend} backend is not supported on this machine")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RaptorQError {}
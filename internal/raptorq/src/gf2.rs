@@ -1,7 +1,76 @@
+use crate::backend;
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+use crate::backend::Backend;
+
 pub fn add_assign_binary(dest: &mut [u64], src: &[u64]) {
     let len = dest.len();
-    for (dest, &src) in dest.iter_mut().zip(&src[..len]) {
+    match backend::active() {
+        // The active backend is always supported, which makes this call safe
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Gfni | Backend::Avx512 => unsafe { add_assign_binary_avx512(dest, &src[..len]) },
+        _ => add_assign_binary_fallback(dest, &src[..len]),
+    }
+}
+
+fn add_assign_binary_fallback(dest: &mut [u64], src: &[u64]) {
+    for (dest, &src) in dest.iter_mut().zip(src) {
         // Addition over GF(2) is defined as XOR
         *dest ^= src;
     }
 }
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+#[target_feature(enable = "avx512f")]
+unsafe fn add_assign_binary_avx512(dest: &mut [u64], src: &[u64]) {
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::*;
+
+        assert_eq!(dest.len(), src.len());
+        let dest_ptr = dest.as_mut_ptr();
+        let src_ptr = src.as_ptr();
+        for i in (0..dest.len()).step_by(8) {
+            // Masked loads and stores handle the final partial vector
+            let remaining = dest.len() - i;
+            let mask = if remaining >= 8 {
+                u8::MAX
+            } else {
+                (1 << remaining) - 1
+            };
+            let dest_vec = _mm512_maskz_loadu_epi64(mask, dest_ptr.add(i) as *const i64);
+            let src_vec = _mm512_maskz_loadu_epi64(mask, src_ptr.add(i) as *const i64);
+            let result = _mm512_xor_si512(dest_vec, src_vec);
+            _mm512_mask_storeu_epi64(dest_ptr.add(i) as *mut i64, mask, result);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use std::vec::Vec;
+
+    use crate::backend::{self, Backend};
+    use crate::gf2::add_assign_binary;
+
+    #[test]
+    fn backends_match() {
+        let _pinned = backend::lock_pinned();
+        // Include a source longer than the destination, which is allowed
+        for length in [0, 1, 7, 8, 9, 100] {
+            let dest: Vec<u64> = (0..length).map(|_| rand::rng().random()).collect();
+            let src: Vec<u64> = (0..length + 3).map(|_| rand::rng().random()).collect();
+            let expected: Vec<u64> = dest.iter().zip(&src).map(|(d, s)| d ^ s).collect();
+            for backend in Backend::supported() {
+                backend::pin(backend).unwrap();
+                let mut result = dest.clone();
+                add_assign_binary(&mut result, &src);
+                assert_eq!(result, expected, "{backend} differs from scalar");
+            }
+            backend::unpin();
+        }
+    }
+}
@@ -0,0 +1,473 @@
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::base::intermediate_tuple;
+use crate::constraint_matrix::{enc_indices, generate_hdpc_rows, ldpc_entries};
+use crate::gf2::add_assign_binary;
+use crate::octet::Octet;
+use crate::symbol::Symbol;
+use crate::systematic_constants::{
+    calculate_p1, extended_source_block_symbols, num_hdpc_symbols, num_intermediate_symbols,
+    num_ldpc_symbols, num_lt_symbols, num_pi_symbols, systematic_index,
+};
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+
+const WORD_WIDTH: usize = 64;
+
+// A bit-packed binary row of the constraint matrix, and the symbol it sums to
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+struct BinaryRow {
+    bits: Vec<u64>,
+    symbol: Symbol,
+}
+
+impl BinaryRow {
+    fn new(columns: usize, symbol: Symbol) -> BinaryRow {
+        BinaryRow {
+            bits: vec![0; columns.div_ceil(WORD_WIDTH)],
+            symbol,
+        }
+    }
+
+    fn set(&mut self, col: usize) {
+        self.bits[col / WORD_WIDTH] |= 1 << (col % WORD_WIDTH);
+    }
+
+    // Returns the first column, at or after start_col, with a non-zero value
+    fn first_one(&self, start_col: usize) -> Option<usize> {
+        let mut word = start_col / WORD_WIDTH;
+        if word >= self.bits.len() {
+            return None;
+        }
+        let mut bits = self.bits[word] & (u64::MAX << (start_col % WORD_WIDTH));
+        loop {
+            if bits != 0 {
+                return Some(word * WORD_WIDTH + bits.trailing_zeros() as usize);
+            }
+            word += 1;
+            if word == self.bits.len() {
+                return None;
+            }
+            bits = self.bits[word];
+        }
+    }
+
+    // Returns the columns with a non-zero value
+    fn ones(bits: &[u64]) -> impl Iterator<Item = usize> + '_ {
+        bits.iter().enumerate().flat_map(|(word, &bits)| {
+            let mut remaining = bits;
+            core::iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+                let bit = remaining.trailing_zeros() as usize;
+                remaining &= remaining - 1;
+                Some(word * WORD_WIDTH + bit)
+            })
+        })
+    }
+
+    fn add_assign(&mut self, other: &BinaryRow) {
+        add_assign_binary(&mut self.bits, &other.bits);
+        self.symbol += &other.symbol;
+    }
+}
+
+// The dense HDPC rows, with the binary rows eliminated from them, and the symbols they sum to
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+struct HdpcRows {
+    rows: Vec<Vec<u8>>,
+    values: Vec<Symbol>,
+    // Number of binary rows eliminated from the HDPC rows so far
+    #[cfg(test)]
+    eliminations: usize,
+}
+
+impl HdpcRows {
+    // Eliminates the binary rows from every HDPC row
+    fn eliminate_all(&mut self, pivots: &[Option<BinaryRow>]) {
+        for (row, value) in self.rows.iter_mut().zip(self.values.iter_mut()) {
+            let _eliminated = HdpcRows::eliminate_row(row, value, pivots, 0);
+            #[cfg(test)]
+            {
+                self.eliminations += _eliminated;
+            }
+        }
+    }
+
+    // Eliminates a newly added binary row, with its leading one in col, from the HDPC rows, along
+    // with any binary rows which that introduces
+    fn eliminate_pivot(&mut self, pivots: &[Option<BinaryRow>], col: usize) {
+        for (row, value) in self.rows.iter_mut().zip(self.values.iter_mut()) {
+            // The other rows are already zero in every column which has a binary row
+            if row[col] != 0 {
+                let _eliminated = HdpcRows::eliminate_row(row, value, pivots, col);
+                #[cfg(test)]
+                {
+                    self.eliminations += _eliminated;
+                }
+            }
+        }
+    }
+
+    // Returns the number of binary rows eliminated from the row
+    fn eliminate_row(
+        row: &mut [u8],
+        value: &mut Symbol,
+        pivots: &[Option<BinaryRow>],
+        start_col: usize,
+    ) -> usize {
+        let mut eliminated = 0;
+        for col in start_col..pivots.len() {
+            if row[col] == 0 {
+                continue;
+            }
+            if let Some(ref pivot) = pivots[col] {
+                let scalar = Octet::new(row[col]);
+                for one in BinaryRow::ones(&pivot.bits) {
+                    row[one] ^= scalar.byte();
+                }
+                if scalar == Octet::one() {
+                    *value += &pivot.symbol;
+                } else {
+                    value.fused_addassign_mul_scalar(&pivot.symbol, &scalar);
+                }
+                eliminated += 1;
+            }
+        }
+        eliminated
+    }
+}
+
+// On-the-fly Gaussian elimination of the constraint matrix, as an alternative to re-solving it
+// from scratch with the PI solver (section 5.4.2) every time a symbol arrives.
+//
+// The binary rows (LDPC rows, padding symbols, and received symbols) are kept in row echelon
+// form as they arrive, such that each new row only costs the elimination it contributes. The
+// dense HDPC rows are only generated once enough rows have been received to make the system
+// solvable. From then on, they are kept eliminated against the binary rows, and each new binary
+// row is only eliminated from them when it arrives, so that a solving attempt only needs to
+// solve the small system of the columns which have no binary row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct IncrementalSymbolDecoder {
+    source_block_symbols: u32,
+    symbol_size: u16,
+    // The row with its leading one in each column, if any
+    pivots: Vec<Option<BinaryRow>>,
+    rank: usize,
+    hdpc: Option<HdpcRows>,
+}
+
+#[allow(non_snake_case)]
+impl IncrementalSymbolDecoder {
+    pub fn size_in_bytes(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        bytes += size_of::<Option<BinaryRow>>() * self.pivots.len();
+        for row in self.pivots.iter().flatten() {
+            bytes += size_of::<u64>() * row.bits.len();
+            bytes += row.symbol.as_bytes().len();
+        }
+        if let Some(ref hdpc) = self.hdpc {
+            for (row, value) in hdpc.rows.iter().zip(hdpc.values.iter()) {
+                bytes += size_of::<Vec<u8>>() + row.len();
+                bytes += size_of::<Symbol>() + value.as_bytes().len();
+            }
+        }
+        bytes
+    }
+
+    pub fn new(source_block_symbols: u32, symbol_size: u16) -> IncrementalSymbolDecoder {
+        let L = num_intermediate_symbols(source_block_symbols) as usize;
+        let S = num_ldpc_symbols(source_block_symbols) as usize;
+        let mut decoder = IncrementalSymbolDecoder {
+            source_block_symbols,
+            symbol_size,
+            pivots: vec![None; L],
+            rank: 0,
+            hdpc: None,
+        };
+
+        let mut ldpc_rows = vec![BinaryRow::new(L, Symbol::zero(symbol_size)); S];
+        ldpc_entries(source_block_symbols, |row, col| ldpc_rows[row].set(col));
+        for row in ldpc_rows {
+            decoder.insert(row);
+        }
+
+        // The padding symbols of the extended source block are known to be zero
+        let Kprime = extended_source_block_symbols(source_block_symbols);
+        for isi in source_block_symbols..Kprime {
+            decoder.add_symbol(isi, Symbol::zero(symbol_size));
+        }
+
+        decoder
+    }
+
+    // Adds the encoding symbol with the given internal symbol id. See section 5.3.1
+    pub fn add_symbol(&mut self, internal_symbol_id: u32, symbol: Symbol) {
+        let L = num_intermediate_symbols(self.source_block_symbols) as usize;
+        let lt_symbols = num_lt_symbols(self.source_block_symbols);
+        let pi_symbols = num_pi_symbols(self.source_block_symbols);
+        let sys_index = systematic_index(self.source_block_symbols);
+        let p1 = calculate_p1(self.source_block_symbols);
+
+        let mut row = BinaryRow::new(L, symbol);
+        let tuple = intermediate_tuple(internal_symbol_id, lt_symbols, sys_index, p1);
+        for col in enc_indices(tuple, lt_symbols, pi_symbols, p1) {
+            row.set(col);
+        }
+        self.insert(row);
+    }
+
+    fn insert(&mut self, mut row: BinaryRow) {
+        let mut col = 0;
+        while let Some(leading) = row.first_one(col) {
+            match self.pivots[leading] {
+                Some(ref pivot) => {
+                    row.add_assign(pivot);
+                    col = leading + 1;
+                }
+                None => {
+                    self.pivots[leading] = Some(row);
+                    self.rank += 1;
+                    if let Some(ref mut hdpc) = self.hdpc {
+                        hdpc.eliminate_pivot(&self.pivots, leading);
+                    }
+                    return;
+                }
+            }
+        }
+        // The row was linearly dependent on those already received, so it's discarded
+    }
+
+    // Attempts to solve for the intermediate symbols. The decoder is left unchanged, so that more
+    // symbols can be added if solving fails, or if the solution is found to be inconsistent.
+    pub fn solve(&mut self) -> Option<Vec<Symbol>> {
+        let L = num_intermediate_symbols(self.source_block_symbols) as usize;
+        let S = num_ldpc_symbols(self.source_block_symbols) as usize;
+        let H = num_hdpc_symbols(self.source_block_symbols) as usize;
+        let Kprime = extended_source_block_symbols(self.source_block_symbols) as usize;
+        if self.rank + H < L {
+            return None;
+        }
+
+        if self.hdpc.is_none() {
+            let matrix = generate_hdpc_rows(Kprime, S, H);
+            let mut hdpc = HdpcRows {
+                rows: (0..H)
+                    .map(|i| (0..L).map(|j| matrix.get(i, j).byte()).collect())
+                    .collect(),
+                values: vec![Symbol::zero(self.symbol_size); H],
+                #[cfg(test)]
+                eliminations: 0,
+            };
+            hdpc.eliminate_all(&self.pivots);
+            self.hdpc = Some(hdpc);
+        }
+
+        let free_columns: Vec<usize> = (0..L).filter(|&col| self.pivots[col].is_none()).collect();
+        let mut free_values = vec![];
+        if !free_columns.is_empty() {
+            free_values = self.solve_free_columns(&free_columns)?;
+        }
+
+        let mut intermediate_symbols: Vec<Option<Symbol>> = vec![None; L];
+        for (col, value) in free_columns.iter().zip(free_values) {
+            intermediate_symbols[*col] = Some(value);
+        }
+        // Back substitution. Each row only has ones at or after its leading column
+        for col in (0..L).rev() {
+            if let Some(ref row) = self.pivots[col] {
+                let mut symbol = row.symbol.clone();
+                for other in BinaryRow::ones(&row.bits).filter(|&other| other != col) {
+                    symbol += intermediate_symbols[other].as_ref().unwrap();
+                }
+                intermediate_symbols[col] = Some(symbol);
+            }
+        }
+
+        Some(
+            intermediate_symbols
+                .into_iter()
+                .map(Option::unwrap)
+                .collect(),
+        )
+    }
+
+    // Solves the HDPC rows, which only remain non-zero in the columns which have no binary row,
+    // for the values of those columns
+    fn solve_free_columns(&self, free_columns: &[usize]) -> Option<Vec<Symbol>> {
+        let hdpc = self.hdpc.as_ref().unwrap();
+        let H = hdpc.rows.len();
+        let mut values = hdpc.values.clone();
+
+        // Reduce the free columns to the identity with Gauss-Jordan elimination
+        let mut submatrix: Vec<Vec<Octet>> = hdpc
+            .rows
+            .iter()
+            .map(|row| {
+                free_columns
+                    .iter()
+                    .map(|&col| Octet::new(row[col]))
+                    .collect()
+            })
+            .collect();
+        for i in 0..free_columns.len() {
+            let pivot_row = (i..H).find(|&row| submatrix[row][i] != Octet::zero())?;
+            submatrix.swap(i, pivot_row);
+            values.swap(i, pivot_row);
+
+            if submatrix[i][i] != Octet::one() {
+                let inverse = Octet::one() / submatrix[i][i].clone();
+                for element in submatrix[i].iter_mut() {
+                    *element = &*element * &inverse;
+                }
+                values[i].mulassign_scalar(&inverse);
+            }
+
+            for row in 0..H {
+                if row == i || submatrix[row][i] == Octet::zero() {
+                    continue;
+                }
+                let scalar = submatrix[row][i].clone();
+                for col in 0..free_columns.len() {
+                    let product = &submatrix[i][col] * &scalar;
+                    submatrix[row][col] += product;
+                }
+                let (dest, src) = if row < i {
+                    let (first, last) = values.split_at_mut(i);
+                    (&mut first[row], &last[0])
+                } else {
+                    let (first, last) = values.split_at_mut(row);
+                    (&mut last[0], &first[i])
+                };
+                if scalar == Octet::one() {
+                    *dest += src;
+                } else {
+                    dest.fused_addassign_mul_scalar(src, &scalar);
+                }
+            }
+        }
+
+        values.truncate(free_columns.len());
+        Some(values)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use std::vec::Vec;
+
+    use super::IncrementalSymbolDecoder;
+    use crate::base::intermediate_tuple;
+    use crate::constraint_matrix::generate_constraint_matrix;
+    use crate::matrix::DenseBinaryMatrix;
+    use crate::pi_solver::fused_inverse_mul_symbols;
+    use crate::symbol::Symbol;
+    use crate::systematic_constants::{
+        calculate_p1, extended_source_block_symbols, num_hdpc_symbols, num_ldpc_symbols,
+        num_lt_symbols, num_pi_symbols, systematic_index,
+    };
+
+    #[test]
+    fn matches_pi_solver() {
+        for &source_symbols in [1, 10, 101].iter() {
+            let kprime = extended_source_block_symbols(source_symbols);
+            let s = num_ldpc_symbols(source_symbols) as usize;
+            let h = num_hdpc_symbols(source_symbols) as usize;
+
+            let mut d = vec![Symbol::zero(4usize); s + h];
+            for _ in 0..kprime {
+                let data: Vec<u8> = (0..4).map(|_| rand::rng().random()).collect();
+                d.push(Symbol::new(data));
+            }
+            let indices: Vec<u32> = (0..kprime).collect();
+            let (a, hdpc) = generate_constraint_matrix::<DenseBinaryMatrix>(kprime, &indices);
+            let (expected, _) = fused_inverse_mul_symbols(a, hdpc, d.clone(), kprime);
+            let expected = expected.unwrap();
+
+            // Repair symbols from the reference solution, generated as in section 5.3.4
+            let lt_symbols = num_lt_symbols(kprime);
+            let pi_symbols = num_pi_symbols(kprime);
+            let sys_index = systematic_index(kprime);
+            let p1 = calculate_p1(kprime);
+            let encode = |isi: u32| {
+                let tuple = intermediate_tuple(isi, lt_symbols, sys_index, p1);
+                let mut symbol = Symbol::zero(4usize);
+                for i in crate::constraint_matrix::enc_indices(tuple, lt_symbols, pi_symbols, p1) {
+                    symbol += &expected[i];
+                }
+                symbol
+            };
+
+            // Lose half of the source symbols, and replace them with repair symbols
+            let mut decoder = IncrementalSymbolDecoder::new(kprime, 4);
+            for isi in (0..kprime).filter(|isi| isi % 2 == 0) {
+                decoder.add_symbol(isi, d[s + h + isi as usize].clone());
+            }
+            let mut result = None;
+            for isi in kprime..(kprime * 2 + 10) {
+                decoder.add_symbol(isi, encode(isi));
+                result = decoder.solve();
+                if result.is_some() {
+                    break;
+                }
+            }
+            assert_eq!(result.unwrap(), expected);
+        }
+    }
+
+    // Solving the block after every symbol must cost no more elimination than solving it once,
+    // after the last symbol
+    #[test]
+    fn attempts_reuse_elimination() {
+        let source_symbols = 101;
+        let kprime = extended_source_block_symbols(source_symbols);
+        let symbols: Vec<Symbol> = (0..kprime * 2)
+            .map(|_| Symbol::new((0..4).map(|_| rand::rng().random()).collect()))
+            .collect();
+        // Lose half of the source symbols. The repair symbols are random, so the solution is
+        // meaningless, but the elimination is the same as for a real block
+        let isis: Vec<u32> = (0..kprime * 2)
+            .filter(|isi| *isi >= kprime || isi % 2 == 0)
+            .collect();
+
+        let mut once = IncrementalSymbolDecoder::new(kprime, 4);
+        for &isi in isis.iter() {
+            once.add_symbol(isi, symbols[isi as usize].clone());
+        }
+        let expected = once.solve().unwrap();
+        let once_eliminations = once.hdpc.as_ref().unwrap().eliminations;
+
+        let mut every = IncrementalSymbolDecoder::new(kprime, 4);
+        let mut attempts = 0;
+        for &isi in isis.iter() {
+            every.add_symbol(isi, symbols[isi as usize].clone());
+            every.solve();
+            if every.hdpc.is_some() {
+                attempts += 1;
+            }
+        }
+        assert!(attempts > 10);
+        assert_eq!(every.solve().unwrap(), expected);
+        let every_eliminations = every.hdpc.as_ref().unwrap().eliminations;
+        // Rows eliminated before a later binary row can be reintroduced by it, so the counts
+        // differ slightly, but must not grow with the number of attempts
+        assert!(every_eliminations < 2 * once_eliminations);
+
+        // Solving again without new symbols does no elimination at all
+        every.solve().unwrap();
+        assert_eq!(
+            every.hdpc.as_ref().unwrap().eliminations,
+            every_eliminations
+        );
+    }
+}
@@ -0,0 +1,367 @@
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::base::{EncodingPacket, ObjectTransmissionInformation, PAYLOAD_ID_SIZE};
+use crate::decoder::Decoder;
+use crate::error::RaptorQError;
+
+/// A checksum attached to each serialized [`EncodingPacket`], so that corrupted packets can be
+/// dropped before they reach the decoder. RaptorQ only corrects erasures, so a single corrupted
+/// symbol would otherwise silently corrupt its entire source block.
+///
+/// The wire format is the serialized packet, followed by the checksum of it in big-endian byte
+/// order, truncated to [`IntegrityCodec::checksum_size`] bytes. The C and WASM bindings use the
+/// same format.
+///
+/// Checksums only detect accidental corruption, and do not protect against forged packets.
+pub trait IntegrityCodec {
+    /// Number of bytes the checksum occupies on the wire, at most 8. Larger sizes are treated
+    /// as 8
+    fn checksum_size(&self) -> usize;
+
+    fn checksum(&self, data: &[u8]) -> u64;
+
+    /// Serializes `packet`, and appends its checksum
+    fn serialize(&self, packet: &EncodingPacket) -> Vec<u8> {
+        let mut serialized = packet.serialize();
+        let checksum = self.checksum(&serialized).to_be_bytes();
+        serialized.extend_from_slice(&checksum[8 - self.checksum_size().min(8)..]);
+        serialized
+    }
+
+    /// Verifies the checksum at the end of `data`, and deserializes the packet before it
+    fn deserialize(&self, data: &[u8]) -> Result<EncodingPacket, RaptorQError> {
+        let checksum_size = self.checksum_size().min(8);
+        if data.len() < PAYLOAD_ID_SIZE + checksum_size {
+            return Err(RaptorQError::InsufficientData {
+                expected: PAYLOAD_ID_SIZE + checksum_size,
+                actual: data.len(),
+            });
+        }
+        let (packet, checksum) = data.split_at(data.len() - checksum_size);
+        let expected = self.checksum(packet).to_be_bytes();
+        if checksum != &expected[8 - checksum_size..] {
+            return Err(RaptorQError::ChecksumMismatch);
+        }
+        EncodingPacket::try_deserialize(packet)
+    }
+}
+
+/// CRC-32C (Castagnoli), as used by iSCSI and SCTP
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Crc32c;
+
+// Reversed polynomial 0x1EDC6F41
+const CRC32C_POLYNOMIAL: u32 = 0x82F6_3B78;
+const CRC32C_TABLE: [u32; 256] = calculate_crc32c_table();
+
+const fn calculate_crc32c_table() -> [u32; 256] {
+    let mut result = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC32C_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        result[i] = crc;
+        i += 1;
+    }
+    result
+}
+
+impl IntegrityCodec for Crc32c {
+    fn checksum_size(&self) -> usize {
+        4
+    }
+
+    fn checksum(&self, data: &[u8]) -> u64 {
+        let mut crc = !0u32;
+        for &byte in data {
+            crc = CRC32C_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+        !crc as u64
+    }
+}
+
+/// 64-bit xxHash. Much faster than CRC-32C without hardware support, and with a lower chance of
+/// missing corruption.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct XxHash64 {
+    seed: u64,
+}
+
+const XXH_PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const XXH_PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const XXH_PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const XXH_PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const XXH_PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+fn xxh64_round(accumulator: u64, input: u64) -> u64 {
+    accumulator
+        .wrapping_add(input.wrapping_mul(XXH_PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(XXH_PRIME64_1)
+}
+
+fn xxh64_merge_round(accumulator: u64, value: u64) -> u64 {
+    (accumulator ^ xxh64_round(0, value))
+        .wrapping_mul(XXH_PRIME64_1)
+        .wrapping_add(XXH_PRIME64_4)
+}
+
+fn read_u64(data: &[u8]) -> u64 {
+    u64::from_le_bytes(*data.first_chunk::<8>().unwrap())
+}
+
+fn read_u32(data: &[u8]) -> u32 {
+    u32::from_le_bytes(*data.first_chunk::<4>().unwrap())
+}
+
+impl XxHash64 {
+    /// Peers must use the same seed. The other bindings always use a seed of 0, which is the
+    /// default.
+    pub fn new(seed: u64) -> XxHash64 {
+        XxHash64 { seed }
+    }
+}
+
+impl IntegrityCodec for XxHash64 {
+    fn checksum_size(&self) -> usize {
+        8
+    }
+
+    fn checksum(&self, data: &[u8]) -> u64 {
+        let mut remaining = data;
+        let mut hash = if data.len() >= 32 {
+            let mut accumulators = [
+                self.seed
+                    .wrapping_add(XXH_PRIME64_1)
+                    .wrapping_add(XXH_PRIME64_2),
+                self.seed.wrapping_add(XXH_PRIME64_2),
+                self.seed,
+                self.seed.wrapping_sub(XXH_PRIME64_1),
+            ];
+            while remaining.len() >= 32 {
+                for (i, accumulator) in accumulators.iter_mut().enumerate() {
+                    *accumulator = xxh64_round(*accumulator, read_u64(&remaining[8 * i..]));
+                }
+                remaining = &remaining[32..];
+            }
+            let mut hash = accumulators[0]
+                .rotate_left(1)
+                .wrapping_add(accumulators[1].rotate_left(7))
+                .wrapping_add(accumulators[2].rotate_left(12))
+                .wrapping_add(accumulators[3].rotate_left(18));
+            for accumulator in accumulators {
+                hash = xxh64_merge_round(hash, accumulator);
+            }
+            hash
+        } else {
+            self.seed.wrapping_add(XXH_PRIME64_5)
+        };
+        hash = hash.wrapping_add(data.len() as u64);
+
+        while remaining.len() >= 8 {
+            hash ^= xxh64_round(0, read_u64(remaining));
+            hash = hash
+                .rotate_left(27)
+                .wrapping_mul(XXH_PRIME64_1)
+                .wrapping_add(XXH_PRIME64_4);
+            remaining = &remaining[8..];
+        }
+        if remaining.len() >= 4 {
+            hash ^= (read_u32(remaining) as u64).wrapping_mul(XXH_PRIME64_1);
+            hash = hash
+                .rotate_left(23)
+                .wrapping_mul(XXH_PRIME64_2)
+                .wrapping_add(XXH_PRIME64_3);
+            remaining = &remaining[4..];
+        }
+        for &byte in remaining {
+            hash ^= (byte as u64).wrapping_mul(XXH_PRIME64_5);
+            hash = hash.rotate_left(11).wrapping_mul(XXH_PRIME64_1);
+        }
+
+        // Avalanche
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(XXH_PRIME64_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(XXH_PRIME64_3);
+        hash ^ (hash >> 32)
+    }
+}
+
+/// Wraps a [`Decoder`], verifying the checksum of each serialized packet before decoding it.
+/// Packets which fail verification, or are otherwise invalid, are dropped and counted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityDecoder<C: IntegrityCodec> {
+    decoder: Decoder,
+    codec: C,
+    rejected_packets: u64,
+}
+
+impl<C: IntegrityCodec> IntegrityDecoder<C> {
+    pub fn new(config: ObjectTransmissionInformation, codec: C) -> IntegrityDecoder<C> {
+        IntegrityDecoder::with_decoder(Decoder::new(config), codec)
+    }
+
+    pub fn with_decoder(decoder: Decoder, codec: C) -> IntegrityDecoder<C> {
+        IntegrityDecoder {
+            decoder,
+            codec,
+            rejected_packets: 0,
+        }
+    }
+
+    /// Verifies and decodes a packet serialized by [`IntegrityCodec::serialize`]. Returns the
+    /// object once it has been decoded.
+    pub fn decode(&mut self, data: &[u8]) -> Option<Vec<u8>> {
+        let result = self
+            .codec
+            .deserialize(data)
+            .and_then(|packet| self.decoder.try_decode(packet));
+        match result {
+            Ok(result) => result,
+            Err(_) => {
+                self.rejected_packets += 1;
+                None
+            }
+        }
+    }
+
+    /// Number of packets which were dropped, because they failed verification or were invalid
+    pub fn rejected_packets(&self) -> u64 {
+        self.rejected_packets
+    }
+
+    pub fn decoder(&self) -> &Decoder {
+        &self.decoder
+    }
+
+    pub fn decoder_mut(&mut self) -> &mut Decoder {
+        &mut self.decoder
+    }
+
+    pub fn into_inner(self) -> Decoder {
+        self.decoder
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::vec::Vec;
+
+    use crate::{Crc32c, EncodingPacket, IntegrityCodec, PayloadId, RaptorQError, XxHash64};
+    #[cfg(not(feature = "python"))]
+    use crate::{Encoder, IntegrityDecoder, ObjectTransmissionInformation};
+
+    #[test]
+    fn checksum_test_vectors() {
+        assert_eq!(Crc32c.checksum(b""), 0);
+        assert_eq!(Crc32c.checksum(b"123456789"), 0xE306_9283);
+        assert_eq!(XxHash64::default().checksum(b""), 0xEF46_DB37_51D8_E999);
+        assert_eq!(XxHash64::default().checksum(b"abc"), 0x44BC_2CF5_AD77_0999);
+        assert_eq!(
+            XxHash64::default().checksum(b"Nobody inspects the spammish repetition"),
+            0xFBCE_A83C_8A37_8BF1
+        );
+        assert_ne!(
+            XxHash64::new(1).checksum(b"abc"),
+            XxHash64::default().checksum(b"abc")
+        );
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let packet = EncodingPacket::new(PayloadId::new(1, 2), vec![3; 100]);
+        for codec in [&Crc32c as &dyn IntegrityCodec, &XxHash64::default()] {
+            let serialized = codec.serialize(&packet);
+            assert_eq!(serialized.len(), 104 + codec.checksum_size());
+            assert_eq!(codec.deserialize(&serialized), Ok(packet.clone()));
+
+            for i in 0..serialized.len() {
+                let mut corrupted = serialized.clone();
+                corrupted[i] ^= 1 << rand::rng().random_range(0..8);
+                assert_eq!(
+                    codec.deserialize(&corrupted),
+                    Err(RaptorQError::ChecksumMismatch)
+                );
+            }
+            assert_eq!(
+                codec.deserialize(&serialized[..3]),
+                Err(RaptorQError::InsufficientData {
+                    expected: 4 + codec.checksum_size(),
+                    actual: 3
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn oversized_checksum() {
+        struct Oversized;
+        impl IntegrityCodec for Oversized {
+            fn checksum_size(&self) -> usize {
+                12
+            }
+
+            fn checksum(&self, data: &[u8]) -> u64 {
+                XxHash64::default().checksum(data)
+            }
+        }
+
+        // Only the 8 bytes of the checksum are written
+        let packet = EncodingPacket::new(PayloadId::new(1, 2), vec![3; 100]);
+        let serialized = Oversized.serialize(&packet);
+        assert_eq!(serialized, XxHash64::default().serialize(&packet));
+        assert_eq!(Oversized.deserialize(&serialized), Ok(packet));
+        assert_eq!(
+            Oversized.deserialize(&serialized[..11]),
+            Err(RaptorQError::InsufficientData {
+                expected: 12,
+                actual: 11
+            })
+        );
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn drops_corrupted_packets() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::with_defaults(data.len() as u64, 500);
+        let encoder = Encoder::new(&data, config);
+        let mut packets: Vec<Vec<u8>> = encoder
+            .get_encoded_packets(10)
+            .iter()
+            .map(|packet| Crc32c.serialize(packet))
+            .collect();
+        packets.shuffle(&mut rand::rng());
+        // Corrupt a few of the packets, which the repair packets make up for
+        for packet in packets[..5].iter_mut() {
+            packet[10] ^= 0x80;
+        }
+
+        let mut decoder = IntegrityDecoder::new(config, Crc32c);
+        let mut result = None;
+        for packet in packets {
+            result = decoder.decode(&packet);
+            if result.is_some() {
+                break;
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+        assert_eq!(decoder.rejected_packets(), 5);
+    }
+}
@@ -17,12 +17,19 @@ extern crate core;
 extern crate std;
 
 mod arraymap;
+mod authentication;
+pub mod backend;
 mod base;
+mod binary_format;
+mod checkpoint;
 mod constraint_matrix;
 mod decoder;
 mod encoder;
+mod error;
 mod gf2;
 mod graph;
+mod incremental_solver;
+mod integrity;
 mod iterators;
 mod matrix;
 mod octet;
@@ -30,34 +37,66 @@ mod octet_matrix;
 mod octets;
 mod operation_vector;
 mod pi_solver;
+mod plan_store;
 #[cfg(feature = "python")]
 mod python;
 mod rng;
 mod sparse_matrix;
 mod sparse_vec;
+#[cfg(feature = "std")]
+mod streaming;
 mod symbol;
 mod systematic_constants;
 mod util;
 
+pub use crate::authentication::AuthenticatedDecoder;
+pub use crate::authentication::HmacSha256;
+pub use crate::authentication::PacketAuthenticator;
+pub use crate::authentication::PacketMac;
+pub use crate::base::CommonOti;
 pub use crate::base::EncodingPacket;
+pub use crate::base::FEC_ENCODING_ID;
 pub use crate::base::ObjectTransmissionInformation;
 pub use crate::base::PayloadId;
+pub use crate::base::SchemeSpecificOti;
 pub use crate::base::partition;
+pub use crate::decoder::BlockFailure;
+pub use crate::decoder::DecodeAttemptPolicy;
+pub use crate::decoder::DecodeFailure;
+pub use crate::decoder::DecodeStatus;
 #[cfg(not(feature = "python"))]
 pub use crate::decoder::Decoder;
+pub use crate::decoder::DecoderConfig;
+pub use crate::decoder::DecodingPlanCache;
+pub use crate::decoder::PartialResult;
+pub use crate::decoder::RejectedPackets;
 pub use crate::decoder::SourceBlockDecoder;
+pub use crate::decoder::SourceBlockDecodingPlan;
 #[cfg(not(feature = "python"))]
 pub use crate::encoder::Encoder;
 pub use crate::encoder::EncoderBuilder;
+pub use crate::encoder::EncodingParameters;
+pub use crate::encoder::InterleavedPacketIter;
+pub use crate::encoder::RepairPacketIter;
 pub use crate::encoder::SourceBlockEncoder;
 pub use crate::encoder::SourceBlockEncodingPlan;
 pub use crate::encoder::calculate_block_offsets;
+pub use crate::error::RaptorQError;
+pub use crate::integrity::Crc32c;
+pub use crate::integrity::IntegrityCodec;
+pub use crate::integrity::IntegrityDecoder;
+pub use crate::integrity::XxHash64;
+pub use crate::plan_store::PlanStore;
 #[cfg(feature = "python")]
 pub use crate::python::Decoder;
 #[cfg(feature = "python")]
 pub use crate::python::Encoder;
 #[cfg(feature = "python")]
 pub use crate::python::raptorq;
+#[cfg(feature = "std")]
+pub use crate::streaming::StreamingDecoder;
+#[cfg(feature = "std")]
+pub use crate::streaming::StreamingEncoder;
 pub use crate::systematic_constants::extended_source_block_symbols;
 
 #[cfg(feature = "benchmarking")]
@@ -77,6 +77,13 @@ pub const OCTET_MUL_HI_BITS: [[u8; 32]; 256] = calculate_octet_mul_hi_table();
 #[cfg(any(feature = "std", test))]
 pub const OCTET_MUL_LOW_BITS: [[u8; 32]; 256] = calculate_octet_mul_low_table();
 
+// GFNI's multiply instruction uses a different reducing polynomial, but multiplication by a
+// constant is linear over GF(2), so it can be done with an affine transform instead. Each entry is
+// the 8x8 bit matrix for vgf2p8affineqb, in which byte 7 - i selects the input bits that are
+// summed into output bit i
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+pub const OCTET_MUL_GF2P8_AFFINE: [u64; 256] = calculate_octet_mul_affine_table();
+
 const fn const_mul(x: usize, y: usize) -> u8 {
     return OCT_EXP[OCT_LOG[x] as usize + OCT_LOG[y] as usize];
 }
@@ -113,6 +120,31 @@ const fn calculate_octet_mul_low_table() -> [[u8; 32]; 256] {
     return result;
 }
 
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+const fn calculate_octet_mul_affine_table() -> [u64; 256] {
+    let mut result = [0; 256];
+    let mut i = 1;
+    while i < 256 {
+        let mut matrix = 0u64;
+        // Input bit k contributes scalar * 2^k to the product
+        let mut k = 0;
+        while k < 8 {
+            let column = const_mul(i, 1 << k);
+            let mut bit = 0;
+            while bit < 8 {
+                if column & (1 << bit) != 0 {
+                    matrix |= 1 << (8 * (7 - bit) + k);
+                }
+                bit += 1;
+            }
+            k += 1;
+        }
+        result[i] = matrix;
+        i += 1;
+    }
+    return result;
+}
+
 const fn calculate_octet_mul_table() -> [[u8; 256]; 256] {
     let mut result = [[0; 256]; 256];
     let mut i = 1;
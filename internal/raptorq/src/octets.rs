@@ -4,7 +4,18 @@ use std::vec::Vec;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+use crate::backend;
+#[cfg(any(
+    all(
+        any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"),
+        feature = "std"
+    ),
+    all(target_arch = "wasm32", target_feature = "simd128")
+))]
+use crate::backend::Backend;
 use crate::octet::OCTET_MUL;
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+use crate::octet::OCTET_MUL_GF2P8_AFFINE;
 #[cfg(all(
     any(
         target_arch = "x86",
@@ -29,9 +40,6 @@ use crate::octet::OCTET_MUL_HI_BITS;
 use crate::octet::OCTET_MUL_LOW_BITS;
 use crate::octet::Octet;
 
-#[cfg(all(target_arch = "aarch64", feature = "std"))]
-use std::arch::is_aarch64_feature_detected;
-
 #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
 use std::arch::wasm32::*;
 
@@ -99,36 +107,22 @@ pub fn fused_addassign_mul_scalar_binary(
     );
 
     assert_eq!(octets.len(), other.len());
-    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
-    {
-        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("bmi1") {
-            unsafe {
-                return fused_addassign_mul_scalar_binary_avx2(octets, other, scalar);
-            }
-        }
-    }
-    #[cfg(all(target_arch = "aarch64", feature = "std"))]
-    {
-        if is_aarch64_feature_detected!("neon") {
-            unsafe {
-                return fused_addassign_mul_scalar_binary_neon(octets, other, scalar);
-            }
-        }
-    }
-    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-    {
-        unsafe {
+    // The active backend is always supported, which makes these calls safe
+    match backend::active() {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Gfni | Backend::Avx512 | Backend::Avx2 => unsafe {
+            return fused_addassign_mul_scalar_binary_avx2(octets, other, scalar);
+        },
+        #[cfg(all(target_arch = "aarch64", feature = "std"))]
+        Backend::Neon => unsafe {
+            return fused_addassign_mul_scalar_binary_neon(octets, other, scalar);
+        },
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        Backend::Simd128 => unsafe {
             return fused_addassign_mul_scalar_binary_simd128(octets, other, scalar);
-        }
-    }
-    #[cfg(all(target_arch = "arm", feature = "std"))]
-    {
-        // TODO: enable when stable
-        // if is_arm_feature_detected!("neon") {
-        //     unsafe {
-        //         return fused_addassign_mul_scalar_binary_neon(octets, other, scalar);
-        //     }
-        // }
+        },
+        // TODO: add NEON on arm, once is_arm_feature_detected is stable
+        _ => {}
     }
 
     // TODO: write an optimized fallback that does call .to_octet_vec()
@@ -397,6 +391,75 @@ unsafe fn mulassign_scalar_neon(octets: &mut [u8], scalar: &Octet) {
     }
 }
 
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+// Mask selecting the bytes of a 64 byte vector which are within the `remaining` bytes of a slice
+fn avx512_tail_mask(remaining: usize) -> u64 {
+    if remaining >= 64 {
+        u64::MAX
+    } else {
+        (1 << remaining) - 1
+    }
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+#[target_feature(enable = "avx512f")]
+#[target_feature(enable = "avx512bw")]
+unsafe fn mulassign_scalar_avx512(octets: &mut [u8], scalar: &Octet) {
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::*;
+
+        let low_mask = _mm512_set1_epi8(0x0F);
+        // The tables hold a copy of the 16 byte lookup table for each 128-bit lane of an AVX2
+        // register, so broadcast them to all four lanes
+        #[allow(clippy::cast_ptr_alignment)]
+        let low_table = _mm512_broadcast_i64x4(_mm256_loadu_si256(
+            OCTET_MUL_LOW_BITS[scalar.byte() as usize].as_ptr() as *const __m256i,
+        ));
+        #[allow(clippy::cast_ptr_alignment)]
+        let hi_table = _mm512_broadcast_i64x4(_mm256_loadu_si256(
+            OCTET_MUL_HI_BITS[scalar.byte() as usize].as_ptr() as *const __m256i,
+        ));
+        let self_ptr = octets.as_mut_ptr();
+        for i in (0..octets.len()).step_by(64) {
+            // Masked loads and stores handle the final partial vector
+            let mask = avx512_tail_mask(octets.len() - i);
+            let self_vec = _mm512_maskz_loadu_epi8(mask, self_ptr.add(i) as *const i8);
+            let low = _mm512_and_si512(self_vec, low_mask);
+            let low_result = _mm512_shuffle_epi8(low_table, low);
+            let hi = _mm512_and_si512(_mm512_srli_epi64(self_vec, 4), low_mask);
+            let hi_result = _mm512_shuffle_epi8(hi_table, hi);
+            let result = _mm512_xor_si512(hi_result, low_result);
+            _mm512_mask_storeu_epi8(self_ptr.add(i) as *mut i8, mask, result);
+        }
+    }
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+#[target_feature(enable = "gfni")]
+#[target_feature(enable = "avx512f")]
+#[target_feature(enable = "avx512bw")]
+unsafe fn mulassign_scalar_gfni(octets: &mut [u8], scalar: &Octet) {
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::*;
+
+        let matrix = _mm512_set1_epi64(OCTET_MUL_GF2P8_AFFINE[scalar.byte() as usize] as i64);
+        let self_ptr = octets.as_mut_ptr();
+        for i in (0..octets.len()).step_by(64) {
+            // Masked loads and stores handle the final partial vector
+            let mask = avx512_tail_mask(octets.len() - i);
+            let self_vec = _mm512_maskz_loadu_epi8(mask, self_ptr.add(i) as *const i8);
+            let result = _mm512_gf2p8affine_epi64_epi8::<0>(self_vec, matrix);
+            _mm512_mask_storeu_epi8(self_ptr.add(i) as *mut i8, mask, result);
+        }
+    }
+}
+
 #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
 #[target_feature(enable = "avx2")]
 unsafe fn mulassign_scalar_avx2(octets: &mut [u8], scalar: &Octet) {
@@ -486,44 +549,22 @@ unsafe fn mulassign_scalar_ssse3(octets: &mut [u8], scalar: &Octet) {
 }
 
 pub fn mulassign_scalar(octets: &mut [u8], scalar: &Octet) {
-    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
-    {
-        if is_x86_feature_detected!("avx2") {
-            unsafe {
-                return mulassign_scalar_avx2(octets, scalar);
-            }
-        }
-        if is_x86_feature_detected!("ssse3") {
-            unsafe {
-                return mulassign_scalar_ssse3(octets, scalar);
-            }
-        }
+    match backend::active() {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Gfni => unsafe { mulassign_scalar_gfni(octets, scalar) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Avx512 => unsafe { mulassign_scalar_avx512(octets, scalar) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Avx2 => unsafe { mulassign_scalar_avx2(octets, scalar) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Ssse3 => unsafe { mulassign_scalar_ssse3(octets, scalar) },
+        #[cfg(all(target_arch = "aarch64", feature = "std"))]
+        Backend::Neon => unsafe { mulassign_scalar_neon(octets, scalar) },
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        Backend::Simd128 => unsafe { mulassign_scalar_simd128(octets, scalar) },
+        // TODO: add NEON on arm, once is_arm_feature_detected is stable
+        _ => mulassign_scalar_fallback(octets, scalar),
     }
-    #[cfg(all(target_arch = "aarch64", feature = "std"))]
-    {
-        if is_aarch64_feature_detected!("neon") {
-            unsafe {
-                return mulassign_scalar_neon(octets, scalar);
-            }
-        }
-    }
-    #[cfg(all(target_arch = "arm", feature = "std"))]
-    {
-        // TODO: enable when stable
-        // if is_arm_feature_detected!("neon") {
-        //     unsafe {
-        //         return mulassign_scalar_neon(octets, scalar);
-        //     }
-        // }
-    }
-    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-    {
-        unsafe {
-            return mulassign_scalar_simd128(octets, scalar);
-        }
-    }
-
-    return mulassign_scalar_fallback(octets, scalar);
 }
 
 #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
@@ -616,6 +657,74 @@ unsafe fn fused_addassign_mul_scalar_neon(octets: &mut [u8], other: &[u8], scala
     }
 }
 
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+#[target_feature(enable = "avx512f")]
+#[target_feature(enable = "avx512bw")]
+unsafe fn fused_addassign_mul_scalar_avx512(octets: &mut [u8], other: &[u8], scalar: &Octet) {
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::*;
+
+        let low_mask = _mm512_set1_epi8(0x0F);
+        // The tables hold a copy of the 16 byte lookup table for each 128-bit lane of an AVX2
+        // register, so broadcast them to all four lanes
+        #[allow(clippy::cast_ptr_alignment)]
+        let low_table = _mm512_broadcast_i64x4(_mm256_loadu_si256(
+            OCTET_MUL_LOW_BITS[scalar.byte() as usize].as_ptr() as *const __m256i,
+        ));
+        #[allow(clippy::cast_ptr_alignment)]
+        let hi_table = _mm512_broadcast_i64x4(_mm256_loadu_si256(
+            OCTET_MUL_HI_BITS[scalar.byte() as usize].as_ptr() as *const __m256i,
+        ));
+        let self_ptr = octets.as_mut_ptr();
+        let other_ptr = other.as_ptr();
+        for i in (0..octets.len()).step_by(64) {
+            // Masked loads and stores handle the final partial vector
+            let mask = avx512_tail_mask(octets.len() - i);
+            // Multiply by scalar
+            let other_vec = _mm512_maskz_loadu_epi8(mask, other_ptr.add(i) as *const i8);
+            let low = _mm512_and_si512(other_vec, low_mask);
+            let low_result = _mm512_shuffle_epi8(low_table, low);
+            let hi = _mm512_and_si512(_mm512_srli_epi64(other_vec, 4), low_mask);
+            let hi_result = _mm512_shuffle_epi8(hi_table, hi);
+            let product = _mm512_xor_si512(hi_result, low_result);
+
+            // Add to self
+            let self_vec = _mm512_maskz_loadu_epi8(mask, self_ptr.add(i) as *const i8);
+            let result = _mm512_xor_si512(self_vec, product);
+            _mm512_mask_storeu_epi8(self_ptr.add(i) as *mut i8, mask, result);
+        }
+    }
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+#[target_feature(enable = "gfni")]
+#[target_feature(enable = "avx512f")]
+#[target_feature(enable = "avx512bw")]
+unsafe fn fused_addassign_mul_scalar_gfni(octets: &mut [u8], other: &[u8], scalar: &Octet) {
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::*;
+
+        let matrix = _mm512_set1_epi64(OCTET_MUL_GF2P8_AFFINE[scalar.byte() as usize] as i64);
+        let self_ptr = octets.as_mut_ptr();
+        let other_ptr = other.as_ptr();
+        for i in (0..octets.len()).step_by(64) {
+            // Masked loads and stores handle the final partial vector
+            let mask = avx512_tail_mask(octets.len() - i);
+            let other_vec = _mm512_maskz_loadu_epi8(mask, other_ptr.add(i) as *const i8);
+            let product = _mm512_gf2p8affine_epi64_epi8::<0>(other_vec, matrix);
+            let self_vec = _mm512_maskz_loadu_epi8(mask, self_ptr.add(i) as *const i8);
+            let result = _mm512_xor_si512(self_vec, product);
+            _mm512_mask_storeu_epi8(self_ptr.add(i) as *mut i8, mask, result);
+        }
+    }
+}
+
 #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
 #[target_feature(enable = "avx2")]
 unsafe fn fused_addassign_mul_scalar_avx2(octets: &mut [u8], other: &[u8], scalar: &Octet) {
@@ -731,44 +840,22 @@ pub fn fused_addassign_mul_scalar(octets: &mut [u8], other: &[u8], scalar: &Octe
     );
 
     assert_eq!(octets.len(), other.len());
-    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
-    {
-        if is_x86_feature_detected!("avx2") {
-            unsafe {
-                return fused_addassign_mul_scalar_avx2(octets, other, scalar);
-            }
-        }
-        if is_x86_feature_detected!("ssse3") {
-            unsafe {
-                return fused_addassign_mul_scalar_ssse3(octets, other, scalar);
-            }
-        }
-    }
-    #[cfg(all(target_arch = "aarch64", feature = "std"))]
-    {
-        if is_aarch64_feature_detected!("neon") {
-            unsafe {
-                return fused_addassign_mul_scalar_neon(octets, other, scalar);
-            }
-        }
-    }
-    #[cfg(all(target_arch = "arm", feature = "std"))]
-    {
-        // TODO: enable when stable
-        // if is_arm_feature_detected!("neon") {
-        //     unsafe {
-        //         return fused_addassign_mul_scalar_neon(octets, other, scalar);
-        //     }
-        // }
+    match backend::active() {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Gfni => unsafe { fused_addassign_mul_scalar_gfni(octets, other, scalar) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Avx512 => unsafe { fused_addassign_mul_scalar_avx512(octets, other, scalar) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Avx2 => unsafe { fused_addassign_mul_scalar_avx2(octets, other, scalar) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Ssse3 => unsafe { fused_addassign_mul_scalar_ssse3(octets, other, scalar) },
+        #[cfg(all(target_arch = "aarch64", feature = "std"))]
+        Backend::Neon => unsafe { fused_addassign_mul_scalar_neon(octets, other, scalar) },
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        Backend::Simd128 => unsafe { fused_addassign_mul_scalar_simd128(octets, other, scalar) },
+        // TODO: add NEON on arm, once is_arm_feature_detected is stable
+        _ => fused_addassign_mul_scalar_fallback(octets, other, scalar),
     }
-    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-    {
-        unsafe {
-            return fused_addassign_mul_scalar_simd128(octets, other, scalar);
-        }
-    }
-
-    return fused_addassign_mul_scalar_fallback(octets, other, scalar);
 }
 
 #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
@@ -931,6 +1018,30 @@ unsafe fn add_assign_neon(octets: &mut [u8], other: &[u8]) {
     }
 }
 
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+#[target_feature(enable = "avx512f")]
+#[target_feature(enable = "avx512bw")]
+unsafe fn add_assign_avx512(octets: &mut [u8], other: &[u8]) {
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::*;
+
+        assert_eq!(octets.len(), other.len());
+        let self_ptr = octets.as_mut_ptr();
+        let other_ptr = other.as_ptr();
+        for i in (0..octets.len()).step_by(64) {
+            // Masked loads and stores handle the final partial vector
+            let mask = avx512_tail_mask(octets.len() - i);
+            let self_vec = _mm512_maskz_loadu_epi8(mask, self_ptr.add(i) as *const i8);
+            let other_vec = _mm512_maskz_loadu_epi8(mask, other_ptr.add(i) as *const i8);
+            let result = _mm512_xor_si512(self_vec, other_vec);
+            _mm512_mask_storeu_epi8(self_ptr.add(i) as *mut i8, mask, result);
+        }
+    }
+}
+
 #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
 #[target_feature(enable = "avx2")]
 unsafe fn add_assign_avx2(octets: &mut [u8], other: &[u8]) {
@@ -1016,43 +1127,20 @@ unsafe fn add_assign_ssse3(octets: &mut [u8], other: &[u8]) {
 }
 
 pub fn add_assign(octets: &mut [u8], other: &[u8]) {
-    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
-    {
-        if is_x86_feature_detected!("avx2") {
-            unsafe {
-                return add_assign_avx2(octets, other);
-            }
-        }
-        if is_x86_feature_detected!("ssse3") {
-            unsafe {
-                return add_assign_ssse3(octets, other);
-            }
-        }
-    }
-    #[cfg(all(target_arch = "aarch64", feature = "std"))]
-    {
-        if is_aarch64_feature_detected!("neon") {
-            unsafe {
-                return add_assign_neon(octets, other);
-            }
-        }
-    }
-    #[cfg(all(target_arch = "arm", feature = "std"))]
-    {
-        // TODO: enable when stable
-        // if is_arm_feature_detected!("neon") {
-        //     unsafe {
-        //         return add_assign_neon(octets, other);
-        //     }
-        // }
+    match backend::active() {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Gfni | Backend::Avx512 => unsafe { add_assign_avx512(octets, other) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Avx2 => unsafe { add_assign_avx2(octets, other) },
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        Backend::Ssse3 => unsafe { add_assign_ssse3(octets, other) },
+        #[cfg(all(target_arch = "aarch64", feature = "std"))]
+        Backend::Neon => unsafe { add_assign_neon(octets, other) },
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        Backend::Simd128 => unsafe { add_assign_simd128(octets, other) },
+        // TODO: add NEON on arm, once is_arm_feature_detected is stable
+        _ => add_assign_fallback(octets, other),
     }
-    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-    {
-        unsafe {
-            return add_assign_simd128(octets, other);
-        }
-    }
-    return add_assign_fallback(octets, other);
 }
 
 #[cfg(feature = "std")]
@@ -1061,10 +1149,11 @@ mod tests {
     use rand::Rng;
     use std::vec::Vec;
 
+    use crate::backend::{self, Backend};
     use crate::octet::Octet;
     use crate::octets::mulassign_scalar;
     use crate::octets::{
-        BinaryOctetVec, fused_addassign_mul_scalar, fused_addassign_mul_scalar_binary,
+        BinaryOctetVec, add_assign, fused_addassign_mul_scalar, fused_addassign_mul_scalar_binary,
     };
 
     #[test]
@@ -1126,4 +1215,41 @@ mod tests {
 
         assert_eq!(expected, data1);
     }
+
+    #[test]
+    fn backends_match() {
+        let _pinned = backend::lock_pinned();
+        // Cover the vector loops, with and without a remainder for the tail
+        for size in [1usize, 31, 64, 65, 1000 + 7] {
+            let scalar = Octet::new(rand::rng().random_range(2..255));
+            let data1: Vec<u8> = (0..size).map(|_| rand::rng().random()).collect();
+            let data2: Vec<u8> = (0..size).map(|_| rand::rng().random()).collect();
+            let binary_vec: Vec<u64> = (0..size.div_ceil(64))
+                .map(|_| rand::rng().random())
+                .collect();
+            let binary_octet_vec = BinaryOctetVec::new(binary_vec, size);
+
+            let run_all = || {
+                let mut results = vec![data1.clone(); 5];
+                add_assign(&mut results[0], &data2);
+                mulassign_scalar(&mut results[1], &scalar);
+                fused_addassign_mul_scalar(&mut results[2], &data2, &scalar);
+                fused_addassign_mul_scalar_binary(&mut results[3], &binary_octet_vec, &scalar);
+                fused_addassign_mul_scalar_binary(
+                    &mut results[4],
+                    &binary_octet_vec,
+                    &Octet::one(),
+                );
+                results
+            };
+
+            backend::pin(Backend::Scalar).unwrap();
+            let expected = run_all();
+            for backend in Backend::supported() {
+                backend::pin(backend).unwrap();
+                assert_eq!(run_all(), expected, "{backend} differs from scalar");
+            }
+            backend::unpin();
+        }
+    }
 }
@@ -5,8 +5,12 @@ use std::vec::Vec;
 use alloc::vec::Vec;
 
 use crate::octet::Octet;
+#[cfg(feature = "parallel")]
+use crate::octets::{add_assign, fused_addassign_mul_scalar, mulassign_scalar};
 use crate::symbol::Symbol;
 use crate::util::get_both_indices;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
@@ -59,6 +63,88 @@ pub fn perform_op(op: &SymbolOps, symbols: &mut Vec<Symbol>) {
     }
 }
 
+// Symbols are only split into stripes of at least this many bytes, so that each stripe amortizes
+// the cost of walking the operation sequence
+#[cfg(feature = "parallel")]
+const MIN_STRIPE_BYTES: usize = 4096;
+
+/// Applies `ops` to `symbols` in order.
+///
+/// With the `parallel` feature, large symbols are split into stripes of columns, and the whole
+/// sequence is run on each stripe concurrently. Every operation acts on each byte position
+/// independently, so this gives the same result as applying the operations to whole symbols.
+pub fn perform_ops(ops: &[SymbolOps], symbols: &mut Vec<Symbol>) {
+    #[cfg(feature = "parallel")]
+    if let Some(stripe_size) = stripe_size(symbols) {
+        perform_ops_striped(ops, symbols, stripe_size);
+        return;
+    }
+    for op in ops {
+        perform_op(op, symbols);
+    }
+}
+
+// Returns the stripe width to split the symbols into, if they are large enough to benefit
+#[cfg(feature = "parallel")]
+fn stripe_size(symbols: &[Symbol]) -> Option<usize> {
+    let symbol_size = symbols.first()?.as_bytes().len();
+    let stripes = (symbol_size / MIN_STRIPE_BYTES).min(rayon::current_num_threads());
+    if stripes < 2 {
+        return None;
+    }
+    // Keep stripes a multiple of the widest SIMD register
+    Some(symbol_size.div_ceil(stripes).next_multiple_of(64))
+}
+
+#[cfg(feature = "parallel")]
+fn perform_ops_striped(ops: &[SymbolOps], symbols: &mut Vec<Symbol>, stripe_size: usize) {
+    // Reorder moves whole symbols, so it is applied between the striped runs
+    for segment in ops.split_inclusive(|op| matches!(op, SymbolOps::Reorder { .. })) {
+        let (arithmetic, reorder) = match segment.split_last() {
+            Some((op @ SymbolOps::Reorder { .. }, rest)) => (rest, Some(op)),
+            _ => (segment, None),
+        };
+
+        let rows = symbols.len();
+        let mut stripes: Vec<Vec<&mut [u8]>> = vec![];
+        for symbol in symbols.iter_mut() {
+            for (i, chunk) in symbol.as_bytes_mut().chunks_mut(stripe_size).enumerate() {
+                if i == stripes.len() {
+                    stripes.push(Vec::with_capacity(rows));
+                }
+                stripes[i].push(chunk);
+            }
+        }
+        stripes.into_par_iter().for_each(|mut stripe| {
+            for op in arithmetic {
+                perform_stripe_op(op, &mut stripe);
+            }
+        });
+
+        if let Some(op) = reorder {
+            perform_op(op, symbols);
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn perform_stripe_op(op: &SymbolOps, stripe: &mut [&mut [u8]]) {
+    match op {
+        SymbolOps::AddAssign { dest, src } => {
+            let (dest, temp) = get_both_indices(stripe, *dest, *src);
+            add_assign(dest, temp);
+        }
+        SymbolOps::MulAssign { dest, scalar } => {
+            mulassign_scalar(stripe[*dest], scalar);
+        }
+        SymbolOps::FMA { dest, src, scalar } => {
+            let (dest, temp) = get_both_indices(stripe, *dest, *src);
+            fused_addassign_mul_scalar(dest, temp, scalar);
+        }
+        SymbolOps::Reorder { .. } => unreachable!(),
+    }
+}
+
 #[cfg(feature = "std")]
 #[cfg(test)]
 mod tests {
@@ -67,6 +153,8 @@ mod tests {
 
     use crate::octet::Octet;
     use crate::operation_vector::{SymbolOps, perform_op};
+    #[cfg(feature = "parallel")]
+    use crate::operation_vector::{perform_ops, perform_ops_striped};
     use crate::symbol::Symbol;
 
     #[test]
@@ -217,4 +305,44 @@ mod tests {
         assert_eq!(data[8].as_bytes()[0], 2);
         assert_eq!(data[9].as_bytes()[0], 4);
     }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_striped() {
+        let rows = 20;
+        let symbol_size = 3000;
+        let mut ops = vec![];
+        for i in 0..200 {
+            let dest = rand::rng().random_range(0..rows);
+            let src = (dest + rand::rng().random_range(1..rows)) % rows;
+            let scalar = Octet::new(rand::rng().random_range(2..=255));
+            ops.push(match i % 3 {
+                0 => SymbolOps::AddAssign { dest, src },
+                1 => SymbolOps::MulAssign { dest, scalar },
+                _ => SymbolOps::FMA { dest, src, scalar },
+            });
+            if i == 100 {
+                ops.push(SymbolOps::Reorder {
+                    order: (0..rows).rev().collect(),
+                });
+            }
+        }
+
+        let data: Vec<Symbol> = (0..rows)
+            .map(|_| Symbol::new((0..symbol_size).map(|_| rand::rng().random()).collect()))
+            .collect();
+        let mut expected = data.clone();
+        for op in ops.iter() {
+            perform_op(op, &mut expected);
+        }
+
+        // Includes a stripe which is narrower than the others
+        let mut striped = data.clone();
+        perform_ops_striped(&ops, &mut striped, 1024);
+        assert_eq!(striped, expected);
+
+        let mut result = data;
+        perform_ops(&ops, &mut result);
+        assert_eq!(result, expected);
+    }
 }
@@ -14,13 +14,12 @@ use crate::matrix::BinaryMatrix;
 use crate::octet::Octet;
 use crate::octet_matrix::DenseOctetMatrix;
 use crate::octets::BinaryOctetVec;
-use crate::operation_vector::SymbolOps;
+use crate::operation_vector::{SymbolOps, perform_ops};
 use crate::symbol::Symbol;
 use crate::systematic_constants::num_hdpc_symbols;
 use crate::systematic_constants::num_intermediate_symbols;
 use crate::systematic_constants::num_ldpc_symbols;
 use crate::systematic_constants::num_pi_symbols;
-use crate::util::get_both_indices;
 
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 enum RowOp {
@@ -516,22 +515,7 @@ impl<T: BinaryMatrix> IntermediateSymbolDecoder<T> {
 
     #[inline(never)]
     fn apply_deferred_symbol_ops(&mut self) {
-        for op in self.deferred_D_ops.iter() {
-            match op {
-                SymbolOps::AddAssign { dest, src } => {
-                    let (dest, temp) = get_both_indices(&mut self.D, *dest, *src);
-                    *dest += temp;
-                }
-                SymbolOps::MulAssign { dest, scalar } => {
-                    self.D[*dest].mulassign_scalar(scalar);
-                }
-                SymbolOps::FMA { dest, src, scalar } => {
-                    let (dest, temp) = get_both_indices(&mut self.D, *dest, *src);
-                    dest.fused_addassign_mul_scalar(temp, scalar);
-                }
-                SymbolOps::Reorder { order: _order } => {}
-            }
-        }
+        perform_ops(&self.deferred_D_ops, &mut self.D);
     }
 
     // Returns true iff all elements in A between [start_row, end_row)
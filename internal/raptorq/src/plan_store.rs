@@ -0,0 +1,335 @@
+#[cfg(feature = "std")]
+use std::{collections::BTreeMap, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::{fs, io, path::Path};
+
+use crate::binary_format::{Reader, write_header, write_varint};
+use crate::encoder::SourceBlockEncodingPlan;
+use crate::error::RaptorQError;
+use crate::octet::Octet;
+use crate::operation_vector::SymbolOps;
+use crate::systematic_constants::{
+    MAX_SOURCE_SYMBOLS_PER_BLOCK, extended_source_block_symbols, num_intermediate_symbols,
+};
+
+// Binary format of a serialized plan:
+//   "RQEP", version (1 byte), source symbol count (u16 BE), operation count (varint), operations
+// Each operation is a tag byte, followed by its fields. Symbol indices are LEB128 varints
+const PLAN_MAGIC: &[u8; 4] = b"RQEP";
+// Binary format of a serialized store:
+//   "RQPS", version (1 byte), plan count (varint), then each plan as length (varint) and bytes
+const STORE_MAGIC: &[u8; 4] = b"RQPS";
+const FORMAT_VERSION: u8 = 1;
+
+const ADD_ASSIGN: u8 = 0;
+const MUL_ASSIGN: u8 = 1;
+const FMA: u8 = 2;
+const REORDER: u8 = 3;
+
+// Reads a symbol index, which must be less than `symbols`
+fn read_index(reader: &mut Reader, symbols: usize) -> Result<usize, RaptorQError> {
+    let index = reader.varint()?;
+    if index >= symbols as u64 {
+        return Err(RaptorQError::InvalidPlan("symbol index out of range"));
+    }
+    Ok(index as usize)
+}
+
+impl SourceBlockEncodingPlan {
+    /// Serializes the plan into a compact binary format, which can be read back with
+    /// [`SourceBlockEncodingPlan::deserialize`].
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![];
+        write_header(&mut out, PLAN_MAGIC, FORMAT_VERSION);
+        out.extend_from_slice(&self.source_symbol_count().to_be_bytes());
+        write_varint(&mut out, self.operations().len() as u64);
+        for op in self.operations() {
+            match op {
+                SymbolOps::AddAssign { dest, src } => {
+                    out.push(ADD_ASSIGN);
+                    write_varint(&mut out, *dest as u64);
+                    write_varint(&mut out, *src as u64);
+                }
+                SymbolOps::MulAssign { dest, scalar } => {
+                    out.push(MUL_ASSIGN);
+                    write_varint(&mut out, *dest as u64);
+                    out.push(scalar.byte());
+                }
+                SymbolOps::FMA { dest, src, scalar } => {
+                    out.push(FMA);
+                    write_varint(&mut out, *dest as u64);
+                    write_varint(&mut out, *src as u64);
+                    out.push(scalar.byte());
+                }
+                SymbolOps::Reorder { order } => {
+                    out.push(REORDER);
+                    write_varint(&mut out, order.len() as u64);
+                    for index in order {
+                        write_varint(&mut out, *index as u64);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Deserializes a plan produced by [`SourceBlockEncodingPlan::serialize`].
+    ///
+    /// The plan is validated, so that applying it cannot panic, but it is not checked to
+    /// actually encode. Only load plans from a trusted source.
+    pub fn deserialize(data: &[u8]) -> Result<SourceBlockEncodingPlan, RaptorQError> {
+        let mut reader = Reader::new(data, RaptorQError::InvalidPlan);
+        let plan = SourceBlockEncodingPlan::read(&mut reader)?;
+        reader.finish()?;
+        Ok(plan)
+    }
+
+    fn read(reader: &mut Reader) -> Result<SourceBlockEncodingPlan, RaptorQError> {
+        reader.header(PLAN_MAGIC, FORMAT_VERSION)?;
+        let source_symbol_count = u16::from_be_bytes([reader.byte()?, reader.byte()?]);
+        if source_symbol_count == 0 || source_symbol_count as u32 > MAX_SOURCE_SYMBOLS_PER_BLOCK {
+            return Err(RaptorQError::TooManySourceSymbols(
+                source_symbol_count as u64,
+            ));
+        }
+        let symbols = num_intermediate_symbols(source_symbol_count as u32) as usize;
+
+        // Every operation takes at least two bytes
+        let count = reader.count(2)?;
+        let mut operations = Vec::with_capacity(count);
+        for _ in 0..count {
+            let op = match reader.byte()? {
+                ADD_ASSIGN => {
+                    let dest = read_index(reader, symbols)?;
+                    let src = read_index(reader, symbols)?;
+                    if dest == src {
+                        return Err(RaptorQError::InvalidPlan("operation on a single symbol"));
+                    }
+                    SymbolOps::AddAssign { dest, src }
+                }
+                MUL_ASSIGN => {
+                    let dest = read_index(reader, symbols)?;
+                    let scalar = Octet::new(reader.byte()?);
+                    SymbolOps::MulAssign { dest, scalar }
+                }
+                FMA => {
+                    let dest = read_index(reader, symbols)?;
+                    let src = read_index(reader, symbols)?;
+                    let scalar = reader.byte()?;
+                    if dest == src || scalar < 2 {
+                        return Err(RaptorQError::InvalidPlan("invalid multiply-add"));
+                    }
+                    SymbolOps::FMA {
+                        dest,
+                        src,
+                        scalar: Octet::new(scalar),
+                    }
+                }
+                REORDER => {
+                    if reader.varint()? != symbols as u64 {
+                        return Err(RaptorQError::InvalidPlan("reorder has the wrong length"));
+                    }
+                    let mut seen = vec![false; symbols];
+                    let mut order = Vec::with_capacity(symbols);
+                    for _ in 0..symbols {
+                        let index = read_index(reader, symbols)?;
+                        if seen[index] {
+                            return Err(RaptorQError::InvalidPlan("reorder is not a permutation"));
+                        }
+                        seen[index] = true;
+                        order.push(index);
+                    }
+                    SymbolOps::Reorder { order }
+                }
+                _ => return Err(RaptorQError::InvalidPlan("unknown operation")),
+            };
+            operations.push(op);
+        }
+        Ok(SourceBlockEncodingPlan::from_operations(
+            source_symbol_count,
+            operations,
+        ))
+    }
+}
+
+/// A set of encoding plans, at most one per extended symbol count K'. Since a plan is valid for
+/// every symbol count with the same K', this covers all block sizes with at most 477 plans.
+///
+/// Stores can be saved to, and loaded from, disk, so that services can start with precomputed
+/// plans instead of generating them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlanStore {
+    plans: BTreeMap<u32, SourceBlockEncodingPlan>,
+}
+
+impl PlanStore {
+    pub fn new() -> PlanStore {
+        PlanStore {
+            plans: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.plans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plans.is_empty()
+    }
+
+    /// Returns the plan for blocks of `symbol_count` source symbols, if present
+    pub fn get(&self, symbol_count: u16) -> Option<&SourceBlockEncodingPlan> {
+        self.plans
+            .get(&extended_source_block_symbols(symbol_count as u32))
+    }
+
+    /// Returns the plan for blocks of `symbol_count` source symbols, generating it if necessary
+    pub fn get_or_generate(&mut self, symbol_count: u16) -> &SourceBlockEncodingPlan {
+        self.plans
+            .entry(extended_source_block_symbols(symbol_count as u32))
+            .or_insert_with(|| SourceBlockEncodingPlan::generate(symbol_count))
+    }
+
+    /// Adds a plan, replacing any existing plan for the same extended symbol count
+    pub fn insert(&mut self, plan: SourceBlockEncodingPlan) {
+        self.plans.insert(
+            extended_source_block_symbols(plan.source_symbol_count() as u32),
+            plan,
+        );
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![];
+        write_header(&mut out, STORE_MAGIC, FORMAT_VERSION);
+        write_varint(&mut out, self.plans.len() as u64);
+        for plan in self.plans.values() {
+            let serialized = plan.serialize();
+            write_varint(&mut out, serialized.len() as u64);
+            out.extend_from_slice(&serialized);
+        }
+        out
+    }
+
+    /// Deserializes a store produced by [`PlanStore::serialize`]. See
+    /// [`SourceBlockEncodingPlan::deserialize`].
+    pub fn deserialize(data: &[u8]) -> Result<PlanStore, RaptorQError> {
+        let mut reader = Reader::new(data, RaptorQError::InvalidPlan);
+        reader.header(STORE_MAGIC, FORMAT_VERSION)?;
+        let count = reader.varint()?;
+        let mut store = PlanStore::new();
+        for _ in 0..count {
+            let length = reader.count(1)?;
+            let mut plan_reader = Reader::new(reader.bytes(length)?, RaptorQError::InvalidPlan);
+            let plan = SourceBlockEncodingPlan::read(&mut plan_reader)?;
+            plan_reader.finish()?;
+            store.insert(plan);
+        }
+        reader.finish()?;
+        Ok(store)
+    }
+
+    /// Writes the store to a file
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.serialize())
+    }
+
+    /// Reads a store from a file. Invalid contents are reported as an error of kind
+    /// `InvalidData`, wrapping a [`RaptorQError`].
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<PlanStore> {
+        let data = fs::read(path)?;
+        PlanStore::deserialize(&data)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use std::vec::Vec;
+
+    use crate::{
+        ObjectTransmissionInformation, PlanStore, RaptorQError, SourceBlockEncoder,
+        SourceBlockEncodingPlan,
+    };
+
+    #[test]
+    fn plan_round_trip() {
+        for symbol_count in [1, 10, 101, 1000] {
+            let plan = SourceBlockEncodingPlan::generate(symbol_count);
+            let serialized = plan.serialize();
+            assert_eq!(
+                SourceBlockEncodingPlan::deserialize(&serialized).unwrap(),
+                plan
+            );
+            // Every truncation is rejected, rather than panicking
+            for length in [0, 4, 5, 7, serialized.len() / 2, serialized.len() - 1] {
+                assert!(SourceBlockEncodingPlan::deserialize(&serialized[..length]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_plan() {
+        let mut serialized = SourceBlockEncodingPlan::generate(10).serialize();
+        serialized[0] = b'X';
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(&serialized),
+            Err(RaptorQError::InvalidPlan("unrecognized format"))
+        );
+
+        let mut serialized = SourceBlockEncodingPlan::generate(10).serialize();
+        serialized.push(0);
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(&serialized),
+            Err(RaptorQError::InvalidPlan("trailing data"))
+        );
+
+        // An index beyond the intermediate symbols
+        let mut serialized = SourceBlockEncodingPlan::generate(10).serialize();
+        serialized.truncate(7);
+        serialized.extend_from_slice(&[1, 0, 100, 7]);
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(&serialized),
+            Err(RaptorQError::InvalidPlan("symbol index out of range"))
+        );
+    }
+
+    #[test]
+    fn store() {
+        let mut store = PlanStore::new();
+        // 11 and 12 share K' = 12
+        store.get_or_generate(11);
+        store.get_or_generate(12);
+        store.get_or_generate(100);
+        assert_eq!(store.len(), 2);
+
+        let path = std::env::temp_dir().join(format!(
+            "raptorq-plan-store-{}",
+            rand::rng().random::<u64>()
+        ));
+        store.save(&path).unwrap();
+        let loaded = PlanStore::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, store);
+
+        // A plan generated for K = 11 encodes blocks of K = 12
+        let symbol_size = 16;
+        let data: Vec<u8> = (0..symbol_size * 12)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let plan = loaded.get(12).unwrap();
+        assert_eq!(plan.source_symbol_count(), 11);
+        assert_eq!(
+            SourceBlockEncoder::with_encoding_plan(0, &config, &data, plan),
+            SourceBlockEncoder::new(0, &config, &data)
+        );
+    }
+}
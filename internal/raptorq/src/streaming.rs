@@ -0,0 +1,434 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::iter;
+use std::vec::Vec;
+
+use crate::base::EncodingPacket;
+use crate::base::ObjectTransmissionInformation;
+use crate::checkpoint::{BlockCheckpoint, Checkpoint, CheckpointWriter};
+use crate::decoder::{
+    DecodeFailure, DecodeStatus, DecoderConfig, RejectedPackets, SourceBlockDecoder,
+};
+use crate::encoder::SourceBlockEncoder;
+use crate::encoder::object_block_offsets;
+use crate::error::RaptorQError;
+use crate::plan_store::PlanStore;
+
+/// Encodes an object read from a stream, one source block at a time, so that only a single
+/// source block needs to be held in memory.
+///
+/// The stream must contain at least `transfer_length()` bytes of the OTI. Any bytes after that
+/// are not read.
+pub struct StreamingEncoder<R: Read> {
+    reader: R,
+    config: ObjectTransmissionInformation,
+    block_offsets: Vec<(u64, u64)>,
+    next_block: usize,
+    buffer: Vec<u8>,
+    plans: PlanStore,
+}
+
+impl<R: Read> StreamingEncoder<R> {
+    pub fn new(reader: R, config: ObjectTransmissionInformation) -> StreamingEncoder<R> {
+        StreamingEncoder::with_plan_store(reader, config, PlanStore::new())
+    }
+
+    /// Like [`StreamingEncoder::new`], but takes encoding plans from `plans`. Any plans which
+    /// have to be generated are added to it, and it can be recovered with
+    /// [`StreamingEncoder::into_plan_store`].
+    pub fn with_plan_store(
+        reader: R,
+        config: ObjectTransmissionInformation,
+        plans: PlanStore,
+    ) -> StreamingEncoder<R> {
+        StreamingEncoder {
+            reader,
+            config,
+            block_offsets: object_block_offsets(&config),
+            next_block: 0,
+            buffer: vec![],
+            plans,
+        }
+    }
+
+    pub fn into_plan_store(self) -> PlanStore {
+        self.plans
+    }
+
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Number of source blocks which have not yet been read
+    pub fn remaining_blocks(&self) -> usize {
+        self.block_offsets.len() - self.next_block
+    }
+
+    /// Reads the next source block from the stream and returns its encoder, or `None` once
+    /// every block has been read.
+    ///
+    /// Returns an error of kind `UnexpectedEof` if the stream ends before the transfer length.
+    pub fn next_block(&mut self) -> io::Result<Option<SourceBlockEncoder>> {
+        let Some(&(start, end)) = self.block_offsets.get(self.next_block) else {
+            return Ok(None);
+        };
+        // Zero pad the last block, if necessary
+        let available = end.min(self.config.transfer_length()).saturating_sub(start);
+        self.buffer.clear();
+        self.buffer.resize((end - start) as usize, 0);
+        self.reader
+            .read_exact(&mut self.buffer[..available as usize])?;
+
+        let symbol_count = ((end - start) / self.config.symbol_size() as u64) as u16;
+        let encoder = SourceBlockEncoder::with_encoding_plan(
+            self.next_block as u8,
+            &self.config,
+            &self.buffer,
+            self.plans.get_or_generate(symbol_count),
+        );
+        self.next_block += 1;
+        Ok(Some(encoder))
+    }
+
+    /// Reads the next source block from the stream and returns all of its source packets,
+    /// followed by `repair_packets_per_block` repair packets, or `None` once every block has been
+    /// read.
+    pub fn next_block_packets(
+        &mut self,
+        repair_packets_per_block: u32,
+    ) -> io::Result<Option<Vec<EncodingPacket>>> {
+        Ok(self.next_block()?.map(|encoder| {
+            let mut packets = encoder.source_packets();
+            packets.extend(encoder.repair_packets(0, repair_packets_per_block));
+            packets
+        }))
+    }
+}
+
+enum BlockState {
+    Decoding(Box<SourceBlockDecoder>),
+    // The block has been written to the sink, and its decoder dropped
+    Written(DecodeStatus),
+}
+
+/// Decodes an object into a seekable sink, such as a file. Each source block is written at its
+/// offset within the object as soon as it is decoded, after which its decoder state is freed,
+/// so memory use is bounded by the source blocks which are still being received.
+pub struct StreamingDecoder<W: Write + Seek> {
+    sink: W,
+    config: ObjectTransmissionInformation,
+    block_offsets: Vec<(u64, u64)>,
+    blocks: Vec<BlockState>,
+    remaining_blocks: usize,
+    // Packets rejected before reaching a source block decoder
+    rejected_packets: RejectedPackets,
+}
+
+impl<W: Write + Seek> StreamingDecoder<W> {
+    pub fn new(sink: W, config: ObjectTransmissionInformation) -> StreamingDecoder<W> {
+        StreamingDecoder::with_decoder_config(sink, config, &DecoderConfig::new())
+    }
+
+    pub fn with_decoder_config(
+        sink: W,
+        config: ObjectTransmissionInformation,
+        decoder_config: &DecoderConfig,
+    ) -> StreamingDecoder<W> {
+        let block_offsets = object_block_offsets(&config);
+        let blocks: Vec<BlockState> = block_offsets
+            .iter()
+            .enumerate()
+            .map(|(i, (start, end))| {
+                BlockState::Decoding(Box::new(SourceBlockDecoder::with_decoder_config(
+                    i as u8,
+                    &config,
+                    end - start,
+                    decoder_config,
+                )))
+            })
+            .collect();
+        StreamingDecoder {
+            sink,
+            config,
+            remaining_blocks: blocks.len(),
+            block_offsets,
+            blocks,
+            rejected_packets: RejectedPackets::default(),
+        }
+    }
+
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Adds a packet. If it completes its source block, the block is written to the sink and
+    /// its source block number is returned.
+    ///
+    /// Packets which do not belong to this object, or whose payload is not exactly one symbol,
+    /// are rejected with an error of kind `InvalidData`, wrapping a [`RaptorQError`].
+    pub fn decode(&mut self, packet: EncodingPacket) -> io::Result<Option<u8>> {
+        let source_block_number = packet.payload_id().source_block_number();
+        let Some(block) = self.blocks.get_mut(source_block_number as usize) else {
+            let error = RaptorQError::InvalidSourceBlockNumber {
+                source_block_number,
+                source_blocks: self.config.source_blocks(),
+            };
+            self.rejected_packets.record(&error);
+            return Err(invalid_data(error));
+        };
+        let BlockState::Decoding(block_decoder) = block else {
+            return Ok(None);
+        };
+        match block_decoder
+            .try_decode(iter::once(packet))
+            .map_err(invalid_data)?
+        {
+            Some(data) => {
+                self.write_block(source_block_number as usize, &data)?;
+                Ok(Some(source_block_number))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Attempts to decode every source block which has not been written yet, regardless of the
+    /// attempt policy. Returns the reason the first of them could not be decoded, if any.
+    pub fn try_finish(&mut self) -> io::Result<Result<(), DecodeFailure>> {
+        let mut failure = None;
+        for block_number in 0..self.blocks.len() {
+            let BlockState::Decoding(block_decoder) = &mut self.blocks[block_number] else {
+                continue;
+            };
+            match block_decoder.try_finish() {
+                Ok(data) => self.write_block(block_number, &data)?,
+                Err(reason) => {
+                    failure.get_or_insert(reason);
+                }
+            }
+        }
+        Ok(failure.map_or(Ok(()), Err))
+    }
+
+    // Writes a decoded block at its offset, without any padding beyond the end of the object
+    fn write_block(&mut self, block_number: usize, data: &[u8]) -> io::Result<()> {
+        let (start, end) = self.block_offsets[block_number];
+        let length = end.min(self.config.transfer_length()) - start;
+        self.sink.seek(SeekFrom::Start(start))?;
+        self.sink.write_all(&data[..length as usize])?;
+
+        let BlockState::Decoding(block_decoder) = &self.blocks[block_number] else {
+            unreachable!();
+        };
+        self.blocks[block_number] = BlockState::Written(block_decoder.status());
+        self.remaining_blocks -= 1;
+        if self.remaining_blocks == 0 {
+            self.sink.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Serializes the received symbols of each source block which has not been written yet, in
+    /// the format of [`crate::Decoder::checkpoint`]. Blocks which have been written are only
+    /// marked as such, since their data is already in the sink.
+    ///
+    /// Flush the sink before persisting the checkpoint, so that it never refers to data which was
+    /// lost.
+    pub fn checkpoint(&self) -> Vec<u8> {
+        let mut writer = CheckpointWriter::new(&self.config);
+        for block in self.blocks.iter() {
+            match block {
+                BlockState::Decoding(block_decoder) => writer.partial_block(
+                    block_decoder.status().received_symbols(),
+                    block_decoder.received_symbols(),
+                ),
+                BlockState::Written(_) => writer.written_block(),
+            }
+        }
+        writer.finish()
+    }
+
+    /// Reconstructs a decoder from a checkpoint created by [`StreamingDecoder::checkpoint`], or
+    /// [`crate::Decoder::checkpoint`]. `sink` must be the sink of the checkpointed decoder, since
+    /// blocks which it already wrote are not rewritten.
+    ///
+    /// Invalid checkpoints are rejected with an error of kind `InvalidData`, wrapping a
+    /// [`RaptorQError`].
+    pub fn resume(sink: W, checkpoint: &[u8]) -> io::Result<StreamingDecoder<W>> {
+        StreamingDecoder::resume_with_decoder_config(sink, checkpoint, &DecoderConfig::new())
+    }
+
+    /// Like [`StreamingDecoder::resume`], but configures the decoder with `decoder_config`,
+    /// which is not stored in the checkpoint.
+    pub fn resume_with_decoder_config(
+        sink: W,
+        checkpoint: &[u8],
+        decoder_config: &DecoderConfig,
+    ) -> io::Result<StreamingDecoder<W>> {
+        let checkpoint = Checkpoint::deserialize(checkpoint).map_err(invalid_data)?;
+        let mut decoder =
+            StreamingDecoder::with_decoder_config(sink, checkpoint.config, decoder_config);
+        for (i, block) in checkpoint.blocks.into_iter().enumerate() {
+            let BlockState::Decoding(block_decoder) = &mut decoder.blocks[i] else {
+                unreachable!();
+            };
+            match block {
+                BlockCheckpoint::Partial(packets) => {
+                    if let Some(data) = block_decoder.restore(packets).map_err(invalid_data)? {
+                        decoder.write_block(i, &data)?;
+                    }
+                }
+                BlockCheckpoint::Complete(data) => decoder.write_block(i, &data)?,
+                BlockCheckpoint::Written => {
+                    block_decoder.mark_decoded();
+                    decoder.blocks[i] = BlockState::Written(block_decoder.status());
+                    decoder.remaining_blocks -= 1;
+                }
+            }
+        }
+        Ok(decoder)
+    }
+
+    /// Returns true once every source block has been written to the sink
+    pub fn is_complete(&self) -> bool {
+        self.remaining_blocks == 0
+    }
+
+    /// Returns the reception progress of the entire object, summed over all source blocks.
+    pub fn status(&self) -> DecodeStatus {
+        DecodeStatus::merged(self.blocks.iter().map(block_status))
+            .with_rejected_packets(&self.rejected_packets)
+    }
+
+    /// Returns the reception progress of a single source block, or `None` if the source block
+    /// number is not part of this object.
+    pub fn block_status(&self, source_block_number: u8) -> Option<DecodeStatus> {
+        self.blocks
+            .get(source_block_number as usize)
+            .map(block_status)
+    }
+
+    /// Returns the sink
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+}
+
+fn block_status(block: &BlockState) -> DecodeStatus {
+    match block {
+        BlockState::Decoding(block_decoder) => block_decoder.status(),
+        BlockState::Written(status) => *status,
+    }
+}
+
+fn invalid_data(error: RaptorQError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::boxed::Box;
+    use std::io::{self, Cursor};
+    use std::vec::Vec;
+
+    use crate::{
+        EncodingPacket, ObjectTransmissionInformation, PayloadId, RaptorQError, SourceBlockEncoder,
+        StreamingDecoder, StreamingEncoder,
+    };
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn matches_encoder() {
+        let data: Vec<u8> = (0..10_001).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 4, 2, 8);
+        let encoder = crate::Encoder::new(&data, config);
+
+        let mut streaming = StreamingEncoder::new(Cursor::new(&data), config);
+        assert_eq!(streaming.remaining_blocks(), 4);
+        let mut packets = vec![];
+        while let Some(block_packets) = streaming.next_block_packets(3).unwrap() {
+            packets.extend(block_packets);
+        }
+        assert_eq!(streaming.remaining_blocks(), 0);
+        assert_eq!(packets, encoder.get_encoded_packets(3));
+    }
+
+    #[test]
+    fn truncated_stream() {
+        let data: Vec<u8> = (0..1000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64 + 1, 64, 1, 1, 8);
+        let mut streaming = StreamingEncoder::new(Cursor::new(&data), config);
+        let error = streaming.next_block().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn ignores_trailing_data() {
+        let data: Vec<u8> = (0..1000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(900, 64, 1, 1, 8);
+        let mut streaming = StreamingEncoder::new(Cursor::new(&data), config);
+        let mut padded = data[..900].to_vec();
+        padded.resize(960, 0);
+        assert_eq!(
+            streaming.next_block().unwrap().unwrap(),
+            SourceBlockEncoder::new(0, &config, &padded)
+        );
+        assert!(streaming.next_block().unwrap().is_none());
+    }
+
+    #[test]
+    fn decode_to_sink() {
+        let data: Vec<u8> = (0..10_001).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 4, 1, 8);
+        let mut streaming = StreamingEncoder::new(Cursor::new(&data), config);
+        let mut packets = vec![];
+        while let Some(block_packets) = streaming.next_block_packets(10).unwrap() {
+            packets.extend(block_packets);
+        }
+        // Drop some source packets, so that the repair packets are needed
+        packets.retain(|packet| packet.payload_id().encoding_symbol_id() % 9 != 0);
+        packets.shuffle(&mut rand::rng());
+
+        let mut decoder = StreamingDecoder::new(Cursor::new(vec![]), config);
+        let mut written = vec![];
+        for packet in packets {
+            if let Some(source_block_number) = decoder.decode(packet).unwrap() {
+                // The block's decoder is freed, but its progress is kept
+                assert!(
+                    decoder
+                        .block_status(source_block_number)
+                        .unwrap()
+                        .is_decoded()
+                );
+                written.push(source_block_number);
+            }
+        }
+        written.sort();
+        assert_eq!(written, [0, 1, 2, 3]);
+        assert!(decoder.is_complete());
+        assert!(decoder.status().is_decoded());
+        assert_eq!(decoder.try_finish().unwrap(), Ok(()));
+        assert_eq!(decoder.into_inner().into_inner(), data);
+    }
+
+    #[test]
+    fn decode_invalid_data() {
+        let config = ObjectTransmissionInformation::new(1000, 64, 2, 1, 8);
+        let mut decoder = StreamingDecoder::new(Cursor::new(vec![]), config);
+        let error = decoder
+            .decode(EncodingPacket::new(PayloadId::new(2, 0), vec![0; 64]))
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error
+                .into_inner()
+                .unwrap()
+                .downcast::<RaptorQError>()
+                .unwrap(),
+            Box::new(RaptorQError::InvalidSourceBlockNumber {
+                source_block_number: 2,
+                source_blocks: 2,
+            })
+        );
+    }
+}
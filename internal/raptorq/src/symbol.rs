@@ -46,6 +46,11 @@ impl Symbol {
         &self.value
     }
 
+    #[cfg(feature = "parallel")]
+    pub(crate) fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.value
+    }
+
     /// Consume a symbol into a vector of bytes.
     pub fn into_bytes(self) -> Vec<u8> {
         self.value
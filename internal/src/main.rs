@@ -1,13 +1,21 @@
 use clap::Parser;
-use std::io::{self, Read, Write};
+use std::fs::File;
+use std::io::{self, Read, Seek, Write};
+#[cfg(unix)]
+use std::os::fd::AsFd;
+#[cfg(windows)]
+use std::os::windows::io::AsHandle;
 use anyhow::{Result, Context, bail};
-use raptorq::{Encoder, Decoder, EncodingPacket, ObjectTransmissionInformation};
+use raptorq::{Encoder, Decoder, EncodingPacket, ObjectTransmissionInformation, SourceBlockEncoder, StreamingEncoder};
 
 // Conditional logging macro - only logs when "verbose-logging" feature is enabled
+// Arguments are still type-checked and used when logging is disabled, so values which are only
+// logged don't cause warnings
 macro_rules! log_info {
     ($($arg:tt)*) => {
-        #[cfg(feature = "verbose-logging")]
-        eprintln!($($arg)*);
+        if cfg!(feature = "verbose-logging") {
+            eprintln!($($arg)*);
+        }
     };
 }
 
@@ -52,6 +60,9 @@ struct Args {
     
     #[arg(long, default_value = "8", help = "Symbol alignment in bytes (must be > 0) - ENCODING ONLY")]
     symbol_alignment: u8,
+
+    #[arg(long, help = "Force a SIMD backend (scalar, ssse3, avx2, avx512, gfni, neon, simd128) instead of the detected one")]
+    backend: Option<String>,
 }
 
 fn main() -> Result<()> {
@@ -72,14 +83,47 @@ fn main() -> Result<()> {
         bail!("Symbol size must be divisible by symbol alignment");
     }
     
+    if let Some(name) = &args.backend {
+        let backend = raptorq::backend::Backend::from_name(name)
+            .with_context(|| format!("Unknown backend: {}", name))?;
+        raptorq::backend::pin(backend)?;
+    }
+    log_info!("Using {} backend", raptorq::backend::active());
+
     if args.encode {
         encode_data(&args)
     } else {
-        decode_data(&args)
+        decode_data()
     }
 }
 
 fn encode_data(args: &Args) -> Result<()> {
+    let mut stdout = io::stdout();
+    let mut total_packets = 0;
+
+    // When stdin is redirected from a file, its length is known up front, so the object can be
+    // read and encoded one source block at a time instead of being held in memory
+    if let Some((file, transfer_length)) = stdin_as_file() {
+        if transfer_length == 0 {
+            bail!("No input data received from stdin");
+        }
+        let config = encoding_config(args, transfer_length);
+        write_oti(&mut stdout, &config)?;
+
+        let mut encoder = StreamingEncoder::new(io::BufReader::new(file), config);
+        let block_count = encoder.remaining_blocks();
+        let mut block_idx = 0;
+        while let Some(block_encoder) = encoder.next_block()
+            .context("Failed to read from stdin")? {
+            total_packets += write_block(&mut stdout, &block_encoder, args, block_idx, block_count)?;
+            block_idx += 1;
+        }
+
+        log_info!("✓ Successfully encoded {} bytes into {} packets (streamed input and output)",
+            transfer_length, total_packets);
+        return Ok(());
+    }
+
     // Read input data from stdin
     let mut input_data = Vec::new();
     io::stdin().read_to_end(&mut input_data)
@@ -91,72 +135,125 @@ fn encode_data(args: &Args) -> Result<()> {
 
     // Create encoder - use defaults with user overrides
     // Transfer length is automatically determined from input data size
-    let transfer_length = input_data.len() as u64;
-    let config = ObjectTransmissionInformation::new(
+    let config = encoding_config(args, input_data.len() as u64);
+    let encoder = Encoder::new(&input_data, config);
+
+    write_oti(&mut stdout, &config)?;
+    
+    log_info!("Starting streaming encode - outputting packets as they're generated...");
+    
+    // Stream encoded packets as they're generated (no buffering)
+    let block_count = encoder.get_block_encoders().len();
+    for (block_idx, block_encoder) in encoder.get_block_encoders().iter().enumerate() {
+        total_packets += write_block(&mut stdout, block_encoder, args, block_idx, block_count)?;
+    }
+    
+    log_info!("✓ Successfully encoded {} bytes into {} packets (streamed output)", 
+        input_data.len(), total_packets);
+    Ok(())
+}
+
+fn encoding_config(args: &Args, transfer_length: u64) -> ObjectTransmissionInformation {
+    ObjectTransmissionInformation::new(
         transfer_length,
         args.symbol_size,
         args.source_blocks,
         args.sub_blocks,
         args.symbol_alignment,
-    );
-    let encoder = Encoder::new(&input_data, config);
+    )
+}
+
+// Output OTI header immediately (12 bytes as per RFC6330)
+// This allows the decoder to automatically determine all encoding parameters
+fn write_oti(stdout: &mut io::Stdout, config: &ObjectTransmissionInformation) -> Result<()> {
+    stdout.write_all(&config.serialize())
+        .context("Failed to write OTI header to stdout")
+}
 
-    // Output OTI header immediately (12 bytes as per RFC6330)
-    // This allows the decoder to automatically determine all encoding parameters
-    let oti = config.serialize();
-    io::stdout().write_all(&oti)
-        .context("Failed to write OTI header to stdout")?;
+// Writes all source packets and the requested repair packets of one block, returning the number written
+fn write_block(stdout: &mut io::Stdout, block_encoder: &SourceBlockEncoder, args: &Args,
+    block_idx: usize, block_count: usize) -> Result<usize> {
+    log_info!("Processing source block {} of {}", block_idx + 1, block_count);
+    let mut total_packets = 0;
     
-    log_info!("Starting streaming encode - outputting packets as they're generated...");
+    // Stream source packets immediately
+    let source_packets = block_encoder.source_packets();
+    let source_packet_count = source_packets.len(); // Store length before move
+    for packet in source_packets {
+        let serialized = packet.serialize();
+        stdout.write_all(&serialized)
+            .context("Failed to write source packet to stdout")?;
+        total_packets += 1;
+    }
     
-    // Stream encoded packets as they're generated (no buffering)
-    let mut total_packets = 0;
-    let mut stdout = io::stdout();
+    // Stream repair packets in batches to avoid memory buildup
+    let repair_batch_size = 50; // Process repair packets in smaller batches
+    let mut repair_start = 0;
     
-    for (block_idx, block_encoder) in encoder.get_block_encoders().iter().enumerate() {
-        log_info!("Processing source block {} of {}", block_idx + 1, encoder.get_block_encoders().len());
+    while repair_start < args.repair_symbols {
+        let batch_size = std::cmp::min(repair_batch_size, args.repair_symbols - repair_start);
+        let repair_packets = block_encoder.repair_packets(repair_start, batch_size);
         
-        // Stream source packets immediately
-        let source_packets = block_encoder.source_packets();
-        let source_packet_count = source_packets.len(); // Store length before move
-        for packet in source_packets {
+        for packet in repair_packets {
             let serialized = packet.serialize();
             stdout.write_all(&serialized)
-                .context("Failed to write source packet to stdout")?;
+                .context("Failed to write repair packet to stdout")?;
             total_packets += 1;
         }
         
-        // Stream repair packets in batches to avoid memory buildup
-        let repair_batch_size = 50; // Process repair packets in smaller batches
-        let mut repair_start = 0;
-        
-        while repair_start < args.repair_symbols {
-            let batch_size = std::cmp::min(repair_batch_size, args.repair_symbols - repair_start);
-            let repair_packets = block_encoder.repair_packets(repair_start, batch_size);
-            
-            for packet in repair_packets {
-                let serialized = packet.serialize();
-                stdout.write_all(&serialized)
-                    .context("Failed to write repair packet to stdout")?;
-                total_packets += 1;
-            }
-            
-            repair_start += batch_size;
-            log_info!("  → Generated {} repair packets so far", repair_start);
-        }
-        
-        // Ensure packets are written immediately
-        stdout.flush().context("Failed to flush stdout")?;
-        log_info!("✓ Completed source block {} ({} packets)", block_idx + 1, 
-            source_packet_count + args.repair_symbols as usize);
+        repair_start += batch_size;
+        log_info!("  → Generated {} repair packets so far", repair_start);
     }
     
-    log_info!("✓ Successfully encoded {} bytes into {} packets (streamed output)", 
-        input_data.len(), total_packets);
+    // Ensure packets are written immediately
+    stdout.flush().context("Failed to flush stdout")?;
+    log_info!("✓ Completed source block {} ({} packets)", block_idx + 1, 
+        source_packet_count + args.repair_symbols as usize);
+    Ok(total_packets)
+}
+
+// Returns stdin as a file, along with the number of bytes remaining in it, if it is redirected
+// from a regular file
+fn stdin_as_file() -> Option<(File, u64)> {
+    #[cfg(unix)]
+    let owned = io::stdin().as_fd().try_clone_to_owned().ok()?;
+    #[cfg(windows)]
+    let owned = io::stdin().as_handle().try_clone_to_owned().ok()?;
+    let mut file = File::from(owned);
+    let metadata = file.metadata().ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let position = file.stream_position().ok()?;
+    Some((file, metadata.len().saturating_sub(position)))
+}
+
+// Output: SBN (1 byte) + Block Size (4 bytes, little-endian) + block data
+fn write_decoded_block(sbn: u8, block_data: &[u8]) -> Result<()> {
+    let mut output = Vec::with_capacity(1 + 4 + block_data.len());
+    output.push(sbn);
+    
+    // Write block size as 4-byte little-endian u32
+    let block_size = block_data.len() as u32;
+    output.extend_from_slice(&block_size.to_le_bytes());
+    output.extend_from_slice(block_data);
+    
+    io::stdout().write_all(&output)
+        .context("Failed to write decoded block to stdout")?;
+    io::stdout().flush()
+        .context("Failed to flush stdout")?;
     Ok(())
 }
 
-fn decode_data(args: &Args) -> Result<()> {
+// Reports the number of packets which were skipped because they were invalid
+fn report_rejected_packets(decoder: &Decoder) {
+    let rejected = decoder.status().rejected_packets().total();
+    if rejected > 0 {
+        eprintln!("Skipped {} invalid packets", rejected);
+    }
+}
+
+fn decode_data() -> Result<()> {
     let mut stdin = io::stdin();
     
     // First, read the OTI header (12 bytes) from stdin
@@ -165,7 +262,8 @@ fn decode_data(args: &Args) -> Result<()> {
         .context("Failed to read OTI header from stdin")?;
 
     // Parse ObjectTransmissionInformation (OTI) from stream
-    let config = ObjectTransmissionInformation::deserialize(&oti_buffer);
+    let config = ObjectTransmissionInformation::try_deserialize(&oti_buffer)
+        .context("Invalid OTI header")?;
     
     log_info!("Using OTI from stream:");
     log_info!("  transfer_length: {} bytes", config.transfer_length());
@@ -185,6 +283,7 @@ fn decode_data(args: &Args) -> Result<()> {
     let mut decoder = Decoder::new(config);
     let mut blocks_completed = 0;
     let total_blocks = config.source_blocks() as usize;
+    let mut blocks_written = vec![false; total_blocks];
     
     log_info!("Starting decoding for {} source blocks...", config.source_blocks());
     
@@ -205,30 +304,28 @@ fn decode_data(args: &Args) -> Result<()> {
                 log_info!("Received packet {} for source block {} ({} bytes)", 
                     packets_processed, sbn, packet_size);
                 
-                // Try to decode this specific block - output immediately if it completes
-                if let Some((block_sbn, block_data)) = decoder.try_decode_block(packet) {
+                // Try to decode this specific block - output immediately if it completes.
+                // Invalid packets are skipped, and counted by the decoder
+                let completed = match decoder.try_decode_block(packet) {
+                    Ok(completed) => completed,
+                    Err(e) => {
+                        log_info!("Skipping invalid packet {} for source block {}: {}",
+                            packets_processed, sbn, e);
+                        continue;
+                    }
+                };
+                if let Some((block_sbn, block_data)) = completed {
                     // This block just completed! Output it immediately
                     blocks_completed += 1;
-                    
-                    // Output: SBN (1 byte) + Block Size (4 bytes, little-endian) + block data
-                    let mut output = Vec::with_capacity(1 + 4 + block_data.len());
-                    output.push(block_sbn);
-                    
-                    // Write block size as 4-byte little-endian u32
-                    let block_size = block_data.len() as u32;
-                    output.extend_from_slice(&block_size.to_le_bytes());
-                    output.extend_from_slice(&block_data);
-                    
-                    io::stdout().write_all(&output)
-                        .context("Failed to write decoded block to stdout")?;
-                    io::stdout().flush()
-                        .context("Failed to flush stdout")?;
+                    blocks_written[block_sbn as usize] = true;
+                    write_decoded_block(block_sbn, &block_data)?;
                         
                     log_info!("✓ Successfully decoded source block {} ({} bytes) using {} total packets", 
                         block_sbn, block_data.len(), packets_processed);
                         
                     // Check if all blocks are now complete
                     if blocks_completed == total_blocks {
+                        report_rejected_packets(&decoder);
                         log_info!("✓ All {} source blocks completed!", total_blocks);
                         return Ok(());
                     }
@@ -238,9 +335,30 @@ fn decode_data(args: &Args) -> Result<()> {
                 }
             }
             Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                // End of stream - no more packets available
+                // End of stream - no more packets available, so make a final attempt at every
+                // incomplete block and report why the others failed
                 log_info!("End of stream reached after {} packets", packets_processed);
-                bail!("Failed to decode: stream ended before all blocks could be decoded");
+                report_rejected_packets(&decoder);
+                match decoder.finish() {
+                    Ok(_) => {
+                        for (sbn, &written) in blocks_written.iter().enumerate() {
+                            if !written {
+                                let block_data = decoder.decoded_block(sbn as u8)
+                                    .context("Decoded block is missing")?;
+                                write_decoded_block(sbn as u8, &block_data)?;
+                            }
+                        }
+                        log_info!("✓ All {} source blocks completed!", total_blocks);
+                        return Ok(());
+                    }
+                    Err(failures) => {
+                        for failure in &failures {
+                            eprintln!("Failed to decode {}", failure);
+                        }
+                        bail!("Failed to decode: stream ended after {} packets, with {} of {} source blocks incomplete",
+                            packets_processed, failures.len(), total_blocks);
+                    }
+                }
             }
             Err(e) => {
                 bail!("Failed to read packet {} from stdin: {}", packets_processed + 1, e);
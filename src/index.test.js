@@ -327,6 +327,44 @@ test("raw decode - invalid output format", () => {
 	}
 });
 
+// OTIs written by versions up to 1.11.0 have the FEC Encoding ID in the reserved byte
+test("raw.oti_decode - legacy OTI with FEC Encoding ID in reserved byte", () => {
+	try {
+		const oti = {
+			transfer_length: 1000n,
+			fec_encoding_id: 6n,
+			symbol_size: 64n,
+			num_source_blocks: 1n,
+			num_sub_blocks: 1n,
+			symbol_alignment: 8n,
+		};
+		const oti_bytes = raw.oti_encode(oti);
+		if (oti_bytes[5] !== 0) return false;
+
+		const legacy_bytes = new Uint8Array(oti_bytes);
+		legacy_bytes[5] = 6;
+		const decoded = raw.oti_decode(legacy_bytes);
+		for (const key of Object.keys(oti)) {
+			if (decoded[key] !== oti[key]) return false;
+		}
+
+		// Any other reserved value should throw
+		const invalid_bytes = new Uint8Array(oti_bytes);
+		invalid_bytes[5] = 7;
+		try {
+			raw.oti_decode(invalid_bytes);
+			return false; // Should have thrown
+		} catch (e) {
+			if (!e.message.includes("reserved field")) return false;
+		}
+
+		return true;
+	} catch (error) {
+		console.error("Legacy OTI test error:", error);
+		return false;
+	}
+});
+
 // Test various symbol_alignment values (removed 1 or 8 restriction)
 test("raw.encode - various symbol_alignment values", async () => {
 	const testData = createTestData(200);
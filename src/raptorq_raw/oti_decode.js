@@ -23,8 +23,11 @@ export const oti_decode = (oti_bytes) => {
 		(BigInt(oti_bytes[3]) << 8n) +
 		(BigInt(oti_bytes[4]) << 0n);
 
-	// FEC Encoding ID - 8 bits, fixed for given FEC algorithm
-	const fec_encoding_id = BigInt(oti_bytes[5]);
+	// Reserved - 8 bits, the FEC Encoding ID is not part of the OTI (RFC6330 section 3.3.2)
+	const reserved = BigInt(oti_bytes[5]);
+
+	// FEC Encoding ID - fixed for RaptorQ
+	const fec_encoding_id = 6n;
 
 	// Symbol Size (T) - 16 bits
 	const symbol_size = 0n + (BigInt(oti_bytes[6]) << 8n) + (BigInt(oti_bytes[7]) << 0n);
@@ -40,6 +43,11 @@ export const oti_decode = (oti_bytes) => {
 
 	// Individual validation
 
+	// Older versions wrote the FEC Encoding ID (6) into the reserved field, so it's accepted too
+	if (reserved !== 0n && reserved !== 6n) {
+		throw_error(error_user_payload(`Obtained reserved field (${reserved}) must be zero.`));
+	}
+
 	if (transfer_length === 0n) {
 		throw_error(error_user_payload(`Obtained transfer_length (${transfer_length}) must be non-zero.`));
 	}
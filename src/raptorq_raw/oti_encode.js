@@ -39,6 +39,11 @@ export const oti_encode = (oti_object) => {
 		throw_error(error_user_payload(`Provided fec_encoding_id (${fec_encoding_id}) must fit in 8 bits.`));
 	}
 
+	// Older versions of oti_decode reported 0n, which may have been stored since, so it's accepted too
+	if (fec_encoding_id !== 6n && fec_encoding_id !== 0n) {
+		throw_error(error_user_payload(`Provided fec_encoding_id (${fec_encoding_id}) must be 6 (RaptorQ).`));
+	}
+
 	if (typeof symbol_size !== "bigint") {
 		throw_error(error_user_payload("Provided symbol_size must be bigint."));
 	}
@@ -122,8 +127,8 @@ export const oti_encode = (oti_object) => {
 	oti_bytes[3] = Number((transfer_length >> 8n) & 0xffn);
 	oti_bytes[4] = Number((transfer_length >> 0n) & 0xffn);
 
-	// FEC Encoding ID - 8 bits
-	oti_bytes[5] = Number(fec_encoding_id);
+	// Reserved - 8 bits, the FEC Encoding ID is not part of the OTI (RFC6330 section 3.3.2)
+	oti_bytes[5] = 0;
 
 	// Symbol Size (T) - 16 bits
 	oti_bytes[6] = Number((symbol_size >> 8n) & 0xffn);
@@ -4,6 +4,7 @@
 
 use wasm_bindgen::prelude::*;
 use raptorq::{Encoder, Decoder, SourceBlockDecoder, EncodingPacket, ObjectTransmissionInformation};
+use raptorq::{Crc32c, IntegrityCodec, XxHash64};
 
 #[cfg(feature = "console_error_panic_hook")]
 pub use console_error_panic_hook::set_once as set_panic_hook;
@@ -162,6 +163,7 @@ pub struct RaptorQDecoder {
     decoder: Decoder,
     config: ObjectTransmissionInformation,
     packets_received: u32,
+    rejected_packets: u32,
     is_complete: bool,
     decoded_data: Option<Vec<u8>>,
 }
@@ -188,6 +190,7 @@ impl RaptorQDecoder {
             decoder,
             config,
             packets_received: 0,
+            rejected_packets: 0,
             is_complete: false,
             decoded_data: None,
         })
@@ -216,6 +219,7 @@ impl RaptorQDecoder {
             decoder,
             config,
             packets_received: 0,
+            rejected_packets: 0,
             is_complete: false,
             decoded_data: None,
         })
@@ -279,6 +283,39 @@ impl RaptorQDecoder {
         Ok(self.is_complete)
     }
 
+    /// Verify the checksum of a packet created by `seal_packet()`, and add it to the decoder.
+    /// Packets which have the wrong length or fail verification are dropped, and counted by
+    /// `rejected_packets()`. Returns true if decoding is now complete
+    ///
+    /// # Arguments
+    /// * `codec` - "crc32c" or "xxhash64"
+    /// * `packet` - Serialized encoding packet, followed by its checksum
+    #[wasm_bindgen]
+    pub fn add_verified_packet(&mut self, codec: &str, packet: &[u8]) -> Result<bool, JsValue> {
+        let codec = integrity_codec(codec)?;
+        if self.is_complete {
+            return Ok(true);
+        }
+
+        self.packets_received += 1;
+        let expected_size = 4 + self.config.symbol_size() as usize + codec.checksum_size();
+        if packet.len() != expected_size {
+            self.rejected_packets += 1;
+            return Ok(self.is_complete);
+        }
+
+        match codec.deserialize(packet).and_then(|packet| self.decoder.try_decode(packet)) {
+            Ok(Some(data)) => {
+                self.is_complete = true;
+                self.decoded_data = Some(data);
+            }
+            Ok(None) => {}
+            Err(_) => self.rejected_packets += 1,
+        }
+
+        Ok(self.is_complete)
+    }
+
     /// Check if decoding is complete
     #[wasm_bindgen]
     pub fn is_complete(&self) -> bool {
@@ -291,6 +328,12 @@ impl RaptorQDecoder {
         self.packets_received
     }
 
+    /// Get number of packets dropped by `add_verified_packet()`
+    #[wasm_bindgen]
+    pub fn rejected_packets(&self) -> u32 {
+        self.rejected_packets
+    }
+
     /// Get the decoded data (only valid after is_complete() returns true)
     #[wasm_bindgen]
     pub fn get_result(&self) -> Result<Vec<u8>, JsValue> {
@@ -401,6 +444,29 @@ impl RaptorQBlockDecoder {
     }
 }
 
+fn integrity_codec(codec: &str) -> Result<Box<dyn IntegrityCodec>, JsValue> {
+    match codec {
+        "crc32c" => Ok(Box::new(Crc32c)),
+        "xxhash64" => Ok(Box::new(XxHash64::default())),
+        _ => Err(JsValue::from_str(&format!("Unknown integrity codec: {}", codec))),
+    }
+}
+
+/// Append a checksum to a serialized packet, in the same format as the C bindings'
+/// `raptorq_integrity_seal()`
+///
+/// # Arguments
+/// * `codec` - "crc32c" or "xxhash64"
+/// * `packet` - Serialized encoding packet (PayloadId + symbol data)
+#[wasm_bindgen]
+pub fn seal_packet(codec: &str, packet: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let codec = integrity_codec(codec)?;
+    if packet.len() < 4 {
+        return Err(JsValue::from_str("Packet must be at least 4 bytes"));
+    }
+    Ok(codec.serialize(&EncodingPacket::deserialize(packet)))
+}
+
 /// Utility function to parse OTI
 #[wasm_bindgen]
 pub fn parse_oti(oti: &[u8]) -> Result<js_sys::Object, JsValue> {